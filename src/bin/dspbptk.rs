@@ -1,33 +1,54 @@
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-use clap::Parser;
+use clap::{CommandFactory, Parser, ValueEnum, error::ErrorKind};
 use dspbptk::{
     self,
-    blueprint::Content,
+    blueprint::{Content, Version},
     dspbptk_blueprint::{
         Building,
         editor::{
             fix_uuid::fix_dspbptk_buildings_index,
-            offset::{self, linear_pattern},
+            mirror::{self, Axis},
+            offset::{self, LatitudeCompensation, circular_pattern, grid_pattern, linear_pattern},
+            relocate, rotate,
+            station::{
+                StationSettingsEdit, StorageEdit, StorageFilter, StorageMatch, edit_storage,
+                query_storage,
+            },
         },
+        uuid::UuidAllocator,
     },
+    error::{DspbptkError, DspbptkWarn},
+    item::Item,
+    parameters::station::LogisticRole,
     workflow::{
         self, FileType, LegalBlueprintFileType,
-        io::{classify_file_type, read_file, write_file},
-        process::{process_back_end, process_front_end, process_middle_layer},
+        info::{BlueprintInfo, analyse, json_string},
+        io::{
+            Backup, WriteMode, WriteOptions, classify_file_type, is_stdio, read_file,
+            write_file_with_options,
+        },
+        optimize::process_back_end_optimized,
+        process::{check_warns, process_back_end, process_front_end, process_middle_layer},
+        report::{FileReport, Summary},
     },
 };
-use log::{error, warn};
-use nalgebra::Vector3;
+use log::{error, info, warn};
+use nalgebra::{Vector2, Vector3};
 use rayon::prelude::*;
 use std::{
+    ffi::OsString,
     num::NonZero,
     path::{Path, PathBuf},
+    process::ExitCode,
 };
 use walkdir::WalkDir;
 
 fn collect_files(path_in: &Path) -> Vec<PathBuf> {
+    if is_stdio(path_in) {
+        return vec![path_in.to_path_buf()];
+    }
     WalkDir::new(path_in)
         .into_iter()
         .filter_map(std::result::Result::ok)
@@ -45,6 +66,10 @@ fn generate_output_path(
     relative_path: &Path,
     file_type: &LegalBlueprintFileType,
 ) -> PathBuf {
+    if is_stdio(root_path_out) {
+        return root_path_out.to_path_buf();
+    }
+
     let extension = match file_type {
         LegalBlueprintFileType::Txt => "txt",
         LegalBlueprintFileType::Content => "content",
@@ -61,138 +86,514 @@ fn generate_output_path(
     }
 }
 
-impl workflow::process::DspbptkMap for SubCommand {
-    fn apply(&self, content_in: Content) -> Content {
+/// 编辑子命令与生成uuid所用的种子
+struct SeededEdit<'a> {
+    command: &'a EditCommand,
+    seed: Option<u64>,
+}
+
+impl workflow::process::DspbptkMap for SeededEdit<'_> {
+    fn apply(&self, content_in: Content) -> Result<(Content, Vec<DspbptkWarn>), DspbptkError> {
         let dspbptk_buildings_in = content_in
             .buildings
             .into_iter()
-            .map(|building| Building::try_from(building).unwrap())
-            .collect::<Vec<_>>();
+            .map(Building::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut uuids = UuidAllocator::new(self.seed);
+        uuids.reserve(
+            dspbptk_buildings_in
+                .iter()
+                .filter_map(|building| building.uuid),
+        );
 
-        let dspbptk_buildings_out = fix_dspbptk_buildings_index(match self {
-            SubCommand::LinearPattern(linear_pattern_args) => {
+        let (dspbptk_buildings_out, warns) = fix_dspbptk_buildings_index(match self.command {
+            EditCommand::LinearPattern(linear_pattern_args) => {
                 let basis_vector = Vector3::<f64>::new(
                     linear_pattern_args.x,
                     linear_pattern_args.y,
                     linear_pattern_args.z,
                 );
-                linear_pattern(&dspbptk_buildings_in, &basis_vector, linear_pattern_args.n)
+                linear_pattern(
+                    &dspbptk_buildings_in,
+                    &basis_vector,
+                    linear_pattern_args.n,
+                    &mut uuids,
+                )
             }
-            SubCommand::Offset(offset_args) => {
+            EditCommand::CircularPattern(circular_pattern_args) => circular_pattern(
+                &dspbptk_buildings_in,
+                circular_pattern_args.n,
+                circular_pattern_args.latitude_shift,
+                &Vector2::new(circular_pattern_args.x, circular_pattern_args.y),
+                &mut uuids,
+            ),
+            EditCommand::GridPattern(grid_pattern_args) => grid_pattern(
+                &dspbptk_buildings_in,
+                &Vector2::new(grid_pattern_args.x, grid_pattern_args.y),
+                grid_pattern_args.nx,
+                grid_pattern_args.ny,
+                &Vector2::new(grid_pattern_args.origin_x, grid_pattern_args.origin_y),
+                grid_pattern_args.compensation,
+                &mut uuids,
+            ),
+            EditCommand::Offset(offset_args) => {
                 let basis_vector = Vector3::<f64>::new(offset_args.x, offset_args.y, offset_args.z);
-                offset::offset(dspbptk_buildings_in, &basis_vector)
+                offset::offset(dspbptk_buildings_in, &basis_vector, &mut uuids)
             }
+            EditCommand::Rotate(rotate_args) => {
+                let pivot = Vector3::<f64>::new(rotate_args.x, rotate_args.y, 0.0);
+                rotate::rotate(dspbptk_buildings_in, &pivot, rotate_args.angle)
+            }
+            EditCommand::Relocate(relocate_args) => relocate::relocate(
+                dspbptk_buildings_in,
+                &Vector2::new(relocate_args.from_x, relocate_args.from_y),
+                &Vector2::new(relocate_args.to_x, relocate_args.to_y),
+            ),
+            EditCommand::Mirror(mirror_args) => mirror::mirror(
+                dspbptk_buildings_in,
+                mirror_args.axis,
+                mirror_args.coordinate,
+            ),
+            EditCommand::EditStations(edit_stations_args) => edit_storage(
+                dspbptk_buildings_in,
+                &edit_stations_args.filter.to_filter(),
+                &edit_stations_args.to_storage_edit(),
+                &edit_stations_args.to_settings_edit(),
+            )?,
         });
 
         let buildings_out = dspbptk_buildings_out
             .into_iter()
-            .map(|building| building.try_into().unwrap())
-            .collect::<Vec<_>>();
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Content {
-            buildings_length: u32::try_from(buildings_out.len()).unwrap(),
-            buildings: buildings_out,
-            ..content_in
-        }
+        Ok((
+            Content {
+                buildings_length: u32::try_from(buildings_out.len())
+                    .map_err(DspbptkError::UnexpectBuildingsCount)?,
+                buildings: buildings_out,
+                ..content_in
+            },
+            warns,
+        ))
+    }
+}
+
+fn log_warns(file_path_in: &Path, warns: &[DspbptkWarn]) {
+    for warn in warns {
+        warn!("\"{}\": {:?}", file_path_in.display(), warn);
     }
 }
 
-// TODO 返回处理是否成功
 fn process_one_file(
     file_path_in: &Path,
     file_path_out: &Path,
     zopfli_options: &zopfli::Options,
-    output_type: &LegalBlueprintFileType,
-    sorting_buildings: bool,
-    rounding_local_offset: bool,
-    sub_command: &Option<SubCommand>,
-) -> Option<()> {
-    let blueprint_kind_in = match read_file(file_path_in) {
-        Ok(result) => result,
-        Err(e) => {
-            error!("\"{}\": {:?}", file_path_in.display(), e);
-            return None;
-        }
-    };
+    args: &Args,
+    edits: &[EditCommand],
+) -> Result<FileReport, DspbptkError> {
+    let blueprint_kind_in = read_file(file_path_in)?;
 
-    let (header_data_in, content_data_in) = match process_front_end(&blueprint_kind_in) {
-        Ok((header_data_in, content_data_in, warns_front_end)) => {
-            for warn in warns_front_end {
-                warn!("\"{}\": {:?}", file_path_in.display(), warn);
-            }
-            (header_data_in, content_data_in)
-        }
-        Err(e) => {
-            error!("\"{}\": {:?}", file_path_in.display(), e);
-            return None;
-        }
-    };
+    let (header_data_in, content_data_in, warns_front_end) = process_front_end(&blueprint_kind_in)?;
+    let warns_front_end = check_warns(warns_front_end, args.strict)?;
+    log_warns(file_path_in, &warns_front_end);
 
-    let (header_data_out, content_data_out) = if let Some(command) = sub_command {
+    let edits = edits
+        .iter()
+        .map(|command| SeededEdit {
+            command,
+            seed: args.seed,
+        })
+        .collect::<Vec<_>>();
+    let (header_data_out, content_data_out, warns_middle_layer) = if edits.is_empty() {
+        (header_data_in, content_data_in, Vec::new())
+    } else {
         process_middle_layer(
             header_data_in,
             content_data_in,
-            sorting_buildings,
-            rounding_local_offset,
-            command,
-        )
-    } else {
-        (header_data_in, content_data_in)
+            // 优化模式会在后端尝试所有排序策略，这里不必再排序
+            !args.no_sorting_buildings && !args.optimize,
+            args.rounding_local_offset,
+            edits.as_slice(),
+        )?
     };
+    let warns_middle_layer = check_warns(warns_middle_layer, args.strict)?;
+    log_warns(file_path_in, &warns_middle_layer);
 
-    let blueprint_kind_out = match process_back_end(
-        &header_data_out,
-        &content_data_out,
-        zopfli_options,
-        output_type,
-    ) {
-        Ok(result) => result,
-        Err(e) => {
-            error!("\"{}\": {:?}", file_path_in.display(), e);
-            return None;
+    let (blueprint_kind_out, warns_back_end) = match args.type_output {
+        LegalBlueprintFileType::Txt if args.optimize => {
+            let (blueprint_kind_out, report, warns) = process_back_end_optimized(
+                &header_data_out,
+                &content_data_out,
+                zopfli_options,
+                &args.building_version,
+                args.preserve_unknown,
+                args.optimize_finalists,
+            )?;
+            info!("\"{}\": {report}", file_path_in.display());
+            (blueprint_kind_out, warns)
         }
+        _ => process_back_end(
+            &header_data_out,
+            &content_data_out,
+            zopfli_options,
+            &args.type_output,
+            &args.building_version,
+            args.preserve_unknown,
+        )?,
     };
+    let warns_back_end = check_warns(warns_back_end, args.strict)?;
+    log_warns(file_path_in, &warns_back_end);
 
-    match write_file(file_path_out, blueprint_kind_out) {
-        Ok(()) => Some(()),
-        Err(e) => {
-            error!("\"{}\": {:?}", file_path_in.display(), e);
-            None
-        }
-    }
+    let size_out = blueprint_kind_out.size();
+    let outcome = write_file_with_options(
+        file_path_out,
+        blueprint_kind_out,
+        Some(&blueprint_kind_in),
+        &configure_write_options(args),
+    )?;
 
-    // TODO 数据统计
+    Ok(FileReport {
+        path: file_path_in.to_path_buf(),
+        warns: [warns_front_end, warns_middle_layer, warns_back_end].concat(),
+        size_in: blueprint_kind_in.size(),
+        size_out,
+        outcome,
+    })
 }
 
-fn parallel_process(args: &Args) {
+fn parallel_process(args: &Args, edits: &[EditCommand]) -> Summary {
     let zopfli_options = configure_zopfli_options(args);
     let path_in = &args.input;
     let path_out = args.output.as_deref().unwrap_or(path_in);
 
     let files = collect_files(path_in);
 
-    let sorting_buildings = !args.no_sorting_buildings;
-    let rounding_local_offset = args.rounding_local_offset;
-
-    let _result: Vec<Option<()>> = files
+    let results = files
         .par_iter()
         .map(|file_path_in| {
             let file_path_out =
                 generate_output_path(path_in, path_out, file_path_in, &args.type_output);
 
-            process_one_file(
-                file_path_in,
-                &file_path_out,
-                &zopfli_options,
-                &args.type_output,
-                sorting_buildings,
-                rounding_local_offset,
-                &args.subcommand,
-            )
+            let result =
+                process_one_file(file_path_in, &file_path_out, &zopfli_options, args, edits)
+                    .map_err(|e| e.in_file(file_path_in));
+            match &result {
+                Ok(report) if args.dry_run => {
+                    info!("(dry run) {report} -> \"{}\"", file_path_out.display());
+                }
+                Ok(report) => info!("{report}"),
+                Err(e) => error!("{e}"),
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+
+    results.iter().fold(Summary::default(), Summary::record)
+}
+
+fn info_one_file(file_path_in: &Path, strict: bool) -> Result<BlueprintInfo, DspbptkError> {
+    let blueprint_kind_in = read_file(file_path_in)?;
+    let (info, warns) = analyse(&blueprint_kind_in)?;
+    log_warns(file_path_in, &check_warns(warns, strict)?);
+    Ok(info)
+}
+
+fn parallel_info(args: &Args, info_args: &InfoArgs) -> bool {
+    let files = collect_files(&args.input);
+
+    let results = files
+        .par_iter()
+        .map(|file_path_in| {
+            let result =
+                info_one_file(file_path_in, args.strict).map_err(|e| e.in_file(file_path_in));
+            if let Err(e) = &result {
+                error!("{e}");
+            }
+            (file_path_in, result)
+        })
+        .collect::<Vec<_>>();
+
+    let is_success = results.iter().all(|(_, result)| result.is_ok());
+    let infos = results
+        .into_iter()
+        .filter_map(|(file_path_in, result)| result.ok().map(|info| (file_path_in, info)));
+
+    if info_args.aggregate {
+        let info = infos
+            .map(|(_, info)| info)
+            .fold(BlueprintInfo::default(), BlueprintInfo::merge);
+        match info_args.format {
+            InfoFormat::Text => print!("{info}"),
+            InfoFormat::Json => println!("{}", info.to_json()),
+        }
+    } else {
+        match info_args.format {
+            InfoFormat::Text => {
+                for (file_path_in, info) in infos {
+                    println!("\"{}\":", file_path_in.display());
+                    print!("{info}");
+                }
+            }
+            InfoFormat::Json => {
+                let infos = infos
+                    .map(|(file_path_in, info)| {
+                        format!(
+                            "{{\"file\":{},\"info\":{}}}",
+                            json_string(&file_path_in.display().to_string()),
+                            info.to_json()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                println!("[{infos}]");
+            }
+        }
+    }
+
+    is_success
+}
+
+fn query_stations_one_file(
+    file_path_in: &Path,
+    filter: &StorageFilter,
+    strict: bool,
+) -> Result<Vec<StorageMatch>, DspbptkError> {
+    let blueprint_kind_in = read_file(file_path_in)?;
+    let (_, content_data_in, warns) = process_front_end(&blueprint_kind_in)?;
+    log_warns(file_path_in, &check_warns(warns, strict)?);
+    let buildings = content_data_in
+        .buildings
+        .into_iter()
+        .map(Building::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(query_storage(&buildings, filter))
+}
+
+fn format_storage_match(found: &StorageMatch) -> String {
+    let station = Item::try_from(found.station_item_id).map_or_else(
+        |_| found.station_item_id.to_string(),
+        |item| format!("{item:?}"),
+    );
+    let item = i16::try_from(found.storage.item_id)
+        .ok()
+        .and_then(|item_id| Item::try_from(item_id).ok())
+        .map_or_else(
+            || found.storage.item_id.to_string(),
+            |item| format!("{item:?}({})", found.storage.item_id),
+        );
+    format!(
+        "{} {station} #{}: {item} local={:?} remote={:?} max={}",
+        found
+            .uuid
+            .map_or_else(|| "-".to_string(), |uuid| format!("{uuid:032x}")),
+        found.index,
+        found.storage.local_role,
+        found.storage.remote_role,
+        found.storage.max,
+    )
+}
+
+fn parallel_query_stations(args: &Args, filter_args: &StationFilterArgs) -> bool {
+    let files = collect_files(&args.input);
+    let filter = filter_args.to_filter();
+
+    let results = files
+        .par_iter()
+        .map(|file_path_in| {
+            let result = query_stations_one_file(file_path_in, &filter, args.strict)
+                .map_err(|e| e.in_file(file_path_in));
+            if let Err(e) = &result {
+                error!("{e}");
+            }
+            (file_path_in, result)
         })
-        .collect();
+        .collect::<Vec<_>>();
+
+    let is_success = results.iter().all(|(_, result)| result.is_ok());
+    for (file_path_in, matches) in results
+        .into_iter()
+        .filter_map(|(file_path_in, result)| result.ok().map(|matches| (file_path_in, matches)))
+        .filter(|(_, matches)| !matches.is_empty())
+    {
+        println!("\"{}\":", file_path_in.display());
+        for found in &matches {
+            println!("  {}", format_storage_match(found));
+        }
+    }
 
-    // TODO 数据统计
+    is_success
+}
+
+fn configure_write_options(args: &Args) -> WriteOptions {
+    let backup = match (&args.backup_dir, args.backup) {
+        (Some(dir), _) => Backup::Dir(dir.clone()),
+        (None, true) => Backup::Bak,
+        (None, false) => Backup::None,
+    };
+
+    WriteOptions {
+        mode: args.write_mode,
+        backup,
+        dry_run: args.dry_run,
+    }
+}
+
+/// 返回`arg`是否是`command`的选项，以及这个选项是否还需要下一个参数作为值。不是`command`的选项时返回None
+fn option_of(command: &clap::Command, arg: &str) -> Option<bool> {
+    if let Some(option) = arg.strip_prefix("--") {
+        let (name, value) = option
+            .split_once('=')
+            .map_or((option, None), |(name, value)| (name, Some(value)));
+        command
+            .get_arguments()
+            .find(|argument| argument.get_long() == Some(name))
+            .map(|argument| value.is_none() && argument.get_action().takes_values())
+    } else {
+        let mut chars = arg.strip_prefix('-')?.chars();
+        let short = chars.next()?;
+        command
+            .get_arguments()
+            .find(|argument| argument.get_short() == Some(short))
+            .map(|argument| chars.as_str().is_empty() && argument.get_action().takes_values())
+    }
+}
+
+/// 以`-`开头但不是负数的参数视为选项
+fn is_option(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with('-') && arg.parse::<f64>().is_err()
+}
+
+/// clap不支持重复的子命令，因此先把第一个编辑子命令之后的编辑子命令连同其位置参数和选项从参数列表中取出，
+/// 剩余的参数交给clap解析。
+///
+/// 第一个位置参数总是输入路径，不会被当作子命令；可选的位置参数遇到下一个编辑子命令或任何选项时截止，
+/// 不属于当前编辑子命令的选项（例如全局选项）及其值留给clap解析
+fn split_chained_edit_commands(
+    argv: impl IntoIterator<Item = OsString>,
+) -> (Vec<OsString>, Vec<Vec<OsString>>) {
+    let main_command = Args::command();
+    let main_commands = std::iter::once(&main_command)
+        .chain(main_command.get_subcommands())
+        .collect::<Vec<_>>();
+    let edit_command = EditCommand::command();
+    let find_edit_command = |name: &str| {
+        edit_command.get_subcommands().find(|command| {
+            command.get_name() == name || command.get_all_aliases().any(|alias| alias == name)
+        })
+    };
+
+    let mut argv = argv.into_iter();
+    let mut main_argv = argv.next().into_iter().collect::<Vec<_>>();
+    let mut chained_argvs: Vec<Vec<OsString>> = Vec::new();
+    let mut is_input_seen = false;
+    let mut is_first_seen = false;
+    let mut chained_command = None;
+    let mut remaining = 0_usize;
+    // 上一个选项还需要一个值时，记录这个值是否属于当前的编辑子命令
+    let mut value_of_chained = None;
+    for arg in argv {
+        if let Some(is_chained) = value_of_chained.take() {
+            if is_chained {
+                chained_argvs.last_mut().unwrap_or(&mut main_argv).push(arg);
+            } else {
+                main_argv.push(arg);
+            }
+            continue;
+        }
+        let arg_str = arg.to_string_lossy().into_owned();
+
+        if is_option(&arg_str) {
+            remaining = 0;
+            if let Some(takes_value) =
+                chained_command.and_then(|command| option_of(command, &arg_str))
+            {
+                value_of_chained = takes_value.then_some(true);
+                chained_argvs.last_mut().unwrap_or(&mut main_argv).push(arg);
+            } else {
+                value_of_chained = main_commands
+                    .iter()
+                    .find_map(|command| option_of(command, &arg_str))
+                    .and_then(|takes_value| takes_value.then_some(false));
+                main_argv.push(arg);
+            }
+        } else if !is_input_seen {
+            is_input_seen = true;
+            // clap总是把与子命令同名的参数当作子命令，这里改写为等价的相对路径
+            let is_subcommand_name =
+                arg_str == "help" || main_command.find_subcommand(&arg_str).is_some();
+            main_argv.push(if is_subcommand_name {
+                Path::new(".").join(arg).into_os_string()
+            } else {
+                arg
+            });
+        } else if let Some(command) = find_edit_command(&arg_str) {
+            remaining = command.get_positionals().count();
+            if is_first_seen {
+                chained_command = Some(command);
+                chained_argvs.push(vec![arg]);
+            } else {
+                is_first_seen = true;
+                main_argv.push(arg);
+            }
+        } else if remaining > 0 {
+            remaining -= 1;
+            chained_argvs.last_mut().unwrap_or(&mut main_argv).push(arg);
+        } else {
+            main_argv.push(arg);
+        }
+    }
+    (main_argv, chained_argvs)
+}
+
+fn parse_edit_command<I, T>(argv: I) -> Result<EditCommand, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    EditCommand::try_parse_from(
+        std::iter::once(OsString::from(env!("CARGO_PKG_NAME")))
+            .chain(argv.into_iter().map(Into::into)),
+    )
+}
+
+/// 脚本文件中每行一个编辑子命令，忽略空行和以`#`开头的注释
+fn parse_script(path: &Path) -> Result<Vec<EditCommand>, String> {
+    let script = std::fs::read_to_string(path)
+        .map_err(|e| format!("can not read script \"{}\": {e}", path.display()))?;
+    script
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            parse_edit_command(line.split_whitespace())
+                .map_err(|e| format!("\"{}\" line {line_number}: {e}", path.display()))
+        })
+        .collect()
+}
+
+/// 按顺序收集所有编辑操作：子命令，重复的子命令，最后是脚本文件
+fn collect_edit_commands(
+    args: &Args,
+    chained_argvs: Vec<Vec<OsString>>,
+) -> Result<Vec<EditCommand>, String> {
+    let first = match &args.subcommand {
+        Some(SubCommand::Edit(command)) => Some(command.clone()),
+        _ => None,
+    };
+    let chained = chained_argvs
+        .into_iter()
+        .map(|argv| parse_edit_command(argv).unwrap_or_else(|e| e.exit()));
+    let script = args
+        .script
+        .as_deref()
+        .map(parse_script)
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(first.into_iter().chain(chained).chain(script).collect())
 }
 
 const fn configure_zopfli_options(args: &Args) -> zopfli::Options {
@@ -222,6 +623,47 @@ struct LinearPatternArgs {
     n: u32,
 }
 
+#[derive(Parser, Debug, Clone)]
+#[command(allow_negative_numbers = true)]
+struct CircularPatternArgs {
+    #[clap(index = 1)]
+    n: u32,
+
+    #[clap(index = 2, default_value = "0")]
+    latitude_shift: f64,
+
+    #[clap(index = 3, default_value = "0")]
+    x: f64,
+
+    #[clap(index = 4, default_value = "0")]
+    y: f64,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(allow_negative_numbers = true)]
+struct GridPatternArgs {
+    #[clap(index = 1)]
+    x: f64,
+
+    #[clap(index = 2)]
+    y: f64,
+
+    #[clap(index = 3)]
+    nx: u32,
+
+    #[clap(index = 4)]
+    ny: u32,
+
+    #[clap(index = 5, default_value = "none")]
+    compensation: LatitudeCompensation,
+
+    #[clap(index = 6, default_value = "0")]
+    origin_x: f64,
+
+    #[clap(index = 7, default_value = "0")]
+    origin_y: f64,
+}
+
 #[derive(Parser, Debug, Clone)]
 struct OffsetArgs {
     #[clap(index = 1)]
@@ -235,12 +677,245 @@ struct OffsetArgs {
 }
 
 #[derive(Parser, Debug, Clone)]
-enum SubCommand {
+#[command(allow_negative_numbers = true)]
+struct RotateArgs {
+    #[clap(index = 1)]
+    angle: f64,
+
+    #[clap(index = 2, default_value = "0")]
+    x: f64,
+
+    #[clap(index = 3, default_value = "0")]
+    y: f64,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(allow_negative_numbers = true)]
+struct MirrorArgs {
+    #[clap(index = 1)]
+    axis: Axis,
+
+    #[clap(index = 2, default_value = "0")]
+    coordinate: f64,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(allow_negative_numbers = true)]
+struct RelocateArgs {
+    #[clap(index = 1)]
+    from_x: f64,
+
+    #[clap(index = 2)]
+    from_y: f64,
+
+    #[clap(index = 3)]
+    to_x: f64,
+
+    #[clap(index = 4)]
+    to_y: f64,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum InfoFormat {
+    Text,
+    Json,
+}
+
+#[derive(Parser, Debug, Clone)]
+struct InfoArgs {
+    /// Aggregate statistics over all blueprints instead of reporting each file
+    #[clap(long)]
+    aggregate: bool,
+
+    /// Output format: text, json
+    #[clap(long, default_value = "text", value_name = "FORMAT")]
+    format: InfoFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum StationType {
+    Pls,
+    Ils,
+}
+
+impl From<StationType> for Item {
+    fn from(station_type: StationType) -> Self {
+        match station_type {
+            StationType::Pls => Self::行星内物流运输站,
+            StationType::Ils => Self::星际物流运输站,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Role {
+    Storage,
+    Supply,
+    Demand,
+}
+
+impl From<Role> for LogisticRole {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Storage => Self::Storage,
+            Role::Supply => Self::Supply,
+            Role::Demand => Self::Demand,
+        }
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(allow_negative_numbers = true)]
+struct StationFilterArgs {
+    /// Only stations of TYPE: pls, ils
+    #[clap(long, value_name = "TYPE")]
+    station: Option<StationType>,
+
+    /// Only the INDEX-th storage of each station, counting from 1 as in game
+    #[clap(long, value_name = "INDEX")]
+    index: Option<usize>,
+
+    /// Only storages holding item ID (0 for empty storages)
+    #[clap(long, value_name = "ID")]
+    item: Option<i32>,
+
+    /// Only storages with local ROLE: storage, supply, demand
+    #[clap(long, value_name = "ROLE")]
+    local: Option<Role>,
+
+    /// Only storages with remote ROLE: storage, supply, demand
+    #[clap(long, value_name = "ROLE")]
+    remote: Option<Role>,
+}
+
+impl StationFilterArgs {
+    fn to_filter(&self) -> StorageFilter {
+        StorageFilter {
+            station: self.station.map(Item::from),
+            index: self.index,
+            item_id: self.item,
+            local_role: self.local.map(LogisticRole::from),
+            remote_role: self.remote.map(LogisticRole::from),
+        }
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(allow_negative_numbers = true)]
+struct EditStationsArgs {
+    #[command(flatten)]
+    filter: StationFilterArgs,
+
+    /// Set item of matched storages to ID (0 to clear)
+    #[clap(long, value_name = "ID")]
+    set_item: Option<i32>,
+
+    /// Set local ROLE of matched storages: storage, supply, demand
+    #[clap(long, value_name = "ROLE")]
+    set_local: Option<Role>,
+
+    /// Set remote ROLE of matched storages: storage, supply, demand
+    #[clap(long, value_name = "ROLE")]
+    set_remote: Option<Role>,
+
+    /// Set max count of matched storages
+    #[clap(long, value_name = "COUNT")]
+    set_max: Option<i32>,
+
+    /// Set drone trip range of matched stations in degrees, as shown in game (e.g. 20 to 180)
+    #[clap(long, value_name = "DEGREES")]
+    drone_range: Option<f64>,
+
+    /// Set vessel trip range of matched stations in light years (1 ly = 60 AU = 2400000 m)
+    #[clap(long, value_name = "LY")]
+    ship_range: Option<f64>,
+
+    /// Set whether matched stations take items from orbital collectors
+    #[clap(long, value_name = "BOOL")]
+    orbital_collector: Option<bool>,
+
+    /// Set warp enable distance of matched stations in AU (1 AU = 40000 m)
+    #[clap(long, value_name = "AU")]
+    warp_distance: Option<f64>,
+
+    /// Set whether vessels of matched stations must carry space warpers
+    #[clap(long, value_name = "BOOL")]
+    warper_necessary: Option<bool>,
+
+    /// Set minimum drone load of matched stations in percent
+    #[clap(long, value_name = "PERCENT")]
+    drone_delivery: Option<i32>,
+
+    /// Set minimum vessel load of matched stations in percent
+    #[clap(long, value_name = "PERCENT")]
+    ship_delivery: Option<i32>,
+
+    /// Set output stack count of matched stations
+    #[clap(long, value_name = "COUNT")]
+    piler_count: Option<i32>,
+}
+
+impl EditStationsArgs {
+    fn to_storage_edit(&self) -> StorageEdit {
+        StorageEdit {
+            item_id: self.set_item,
+            local_role: self.set_local.map(LogisticRole::from),
+            remote_role: self.set_remote.map(LogisticRole::from),
+            max: self.set_max,
+        }
+    }
+
+    const fn to_settings_edit(&self) -> StationSettingsEdit {
+        StationSettingsEdit {
+            trip_range_drones: self.drone_range,
+            trip_range_ships: self.ship_range,
+            include_orbit_collector: self.orbital_collector,
+            warp_enable_distance: self.warp_distance,
+            warper_necessary: self.warper_necessary,
+            delivery_drones: self.drone_delivery,
+            delivery_ships: self.ship_delivery,
+            piler_count: self.piler_count,
+        }
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+enum EditCommand {
     /// Linear pattern blueprint with vector XYZ and count N
     LinearPattern(LinearPatternArgs),
 
+    /// Circular pattern blueprint N times around the planet axis, shifting each copy by LATITUDE_SHIFT grids along the meridian through XY (default 0 0 0)
+    CircularPattern(CircularPatternArgs),
+
+    /// Grid pattern blueprint NX by NY with steps XY, latitude COMPENSATION (none, stretch, reduce) relative to the row at ORIGIN_X ORIGIN_Y (default none 0 0)
+    GridPattern(GridPatternArgs),
+
     /// Offset blueprint with vector XYZ
     Offset(OffsetArgs),
+
+    /// Rotate blueprint clockwise by ANGLE degrees about pivot XY (default 0 0)
+    Rotate(RotateArgs),
+
+    /// Move blueprint along the sphere from FROM_X FROM_Y to TO_X TO_Y (longitude, latitude in grids)
+    Relocate(RelocateArgs),
+
+    /// Mirror blueprint about the line parallel to AXIS (x, y) through COORDINATE (default 0)
+    Mirror(MirrorArgs),
+
+    /// Rewrite logistics station storages matching the filter, and settings of the stations owning them
+    EditStations(EditStationsArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+enum SubCommand {
+    /// Print statistics of blueprints without writing anything
+    Info(InfoArgs),
+
+    /// Print logistics station storages matching the filter without writing anything
+    QueryStations(StationFilterArgs),
+
+    #[command(flatten)]
+    Edit(EditCommand),
 }
 
 #[derive(Parser, Debug)]
@@ -250,14 +925,14 @@ enum SubCommand {
     about = env!("CARGO_PKG_DESCRIPTION")
 )]
 struct Args {
-    /// Input from file/dir. (*.txt *.content dir/)
+    /// Input from file/dir, or - for stdin. (*.txt *.content dir/ -)
     #[clap(value_name = "INPUT")]
     input: PathBuf,
 
     #[command(subcommand)]
     subcommand: Option<SubCommand>,
 
-    /// Output to file/dir (*.* dir/)
+    /// Output to file/dir, or - for stdout (*.* dir/ -)
     #[clap(long, short, value_name = "OUTPUT", global = true)]
     output: Option<PathBuf>,
 
@@ -265,6 +940,34 @@ struct Args {
     #[clap(long, short, default_value = "txt", value_name = "TYPE", global = true)]
     type_output: LegalBlueprintFileType,
 
+    /// Apply edit commands from FILE (one per line, # for comments) after those on the command line
+    #[clap(long, value_name = "FILE", global = true)]
+    script: Option<PathBuf>,
+
+    /// Write condition: always, smaller (than input), changed (decoded data differs from input)
+    #[clap(long, default_value = "always", value_name = "MODE", global = true)]
+    write_mode: WriteMode,
+
+    /// Keep the overwritten file as *.bak beside it
+    #[clap(long, global = true)]
+    backup: bool,
+
+    /// Keep the overwritten file in DIR instead of *.bak
+    #[clap(long, value_name = "DIR", global = true)]
+    backup_dir: Option<PathBuf>,
+
+    /// Show what would be written without touching disk
+    #[clap(long, global = true)]
+    dry_run: bool,
+
+    /// Building version for older game builds: neg101, neg100, zero
+    #[clap(long, default_value = "neg101", value_name = "VERSION", global = true)]
+    building_version: Version,
+
+    /// Write unknown data after header and content back verbatim.
+    #[clap(long, global = true)]
+    preserve_unknown: bool,
+
     /// Round `local_offset` to 1/300 may make blueprint smaller. Lossy.
     #[clap(long, global = true)]
     rounding_local_offset: bool,
@@ -273,6 +976,24 @@ struct Args {
     #[clap(long, global = true)]
     no_sorting_buildings: bool,
 
+    /// Try every sort strategy and keep the smallest txt output. Lossless, but slower.
+    #[clap(long, global = true)]
+    optimize: bool,
+
+    /// Number of sort strategies (besides the default one) compressed by zopfli in optimize mode,
+    /// picked by a quick flate2 estimate.
+    #[clap(long, global = true, default_value_t = 1)]
+    optimize_finalists: usize,
+
+    /// Seed for generating uuids, so identical inputs always give identical intermediate data.
+    /// Random if not specified.
+    #[clap(long, global = true)]
+    seed: Option<u64>,
+
+    /// Treat any warning (e.g. unexpected MD5F) as an error.
+    #[clap(long, global = true)]
+    strict: bool,
+
     /// Compress arguments: zopfli `iteration_count`
     #[clap(long, default_value = "15", value_name = "COUNT", global = true)]
     iteration_count: NonZero<u64>,
@@ -291,11 +1012,154 @@ struct Args {
     maximum_block_splits: u16,
 }
 
-fn main() {
+fn main() -> ExitCode {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     eprintln!("https://github.com/bWFuanVzYWth/dspbptk");
-    let args = Args::parse();
+    let (argv, chained_argvs) = split_chained_edit_commands(std::env::args_os());
+    let args = Args::parse_from(argv);
+    if args.optimize && matches!(args.type_output, LegalBlueprintFileType::Content) {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--optimize only applies to txt output",
+            )
+            .exit();
+    }
+
+    let edits = match collect_edit_commands(&args, chained_argvs) {
+        Ok(edits) => edits,
+        Err(e) => {
+            error!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let is_success = match &args.subcommand {
+        Some(SubCommand::Info(_)) if !edits.is_empty() => Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "info can not be combined with edit commands",
+            )
+            .exit(),
+        Some(SubCommand::Info(info_args)) => parallel_info(&args, info_args),
+        Some(SubCommand::QueryStations(_)) if !edits.is_empty() => Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "query-stations can not be combined with edit commands",
+            )
+            .exit(),
+        Some(SubCommand::QueryStations(filter_args)) => parallel_query_stations(&args, filter_args),
+        _ => {
+            let summary = parallel_process(&args, &edits);
+            info!("{summary}");
+            summary.is_success()
+        }
+    };
+
+    if is_success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn split(argv: &str) -> (Vec<OsString>, Vec<Vec<OsString>>) {
+        split_chained_edit_commands(argv.split_whitespace().map(OsString::from))
+    }
 
-    parallel_process(&args);
+    fn os_strings(argv: &str) -> Vec<OsString> {
+        argv.split_whitespace().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_split_chained_edit_commands() {
+        // 全局选项不会被当作可选的位置参数
+        let (main_argv, chained_argvs) = split("dspbptk x.txt offset 1 0 0 rotate 90 -o out.txt");
+        assert_eq!(
+            main_argv,
+            os_strings("dspbptk x.txt offset 1 0 0 -o out.txt")
+        );
+        assert_eq!(chained_argvs, vec![os_strings("rotate 90")]);
+        let args = Args::try_parse_from(main_argv).unwrap();
+        assert_eq!(args.output, Some(PathBuf::from("out.txt")));
+        assert!(
+            chained_argvs
+                .into_iter()
+                .all(|argv| parse_edit_command(argv).is_ok())
+        );
+
+        let (main_argv, chained_argvs) =
+            split("dspbptk x.txt offset 1 0 0 mirror x --strict -o out.txt");
+        assert_eq!(
+            main_argv,
+            os_strings("dspbptk x.txt offset 1 0 0 --strict -o out.txt")
+        );
+        assert_eq!(chained_argvs, vec![os_strings("mirror x")]);
+
+        // 与子命令同名的输入路径
+        let (main_argv, chained_argvs) = split("dspbptk offset offset 1 0 0 rotate 90");
+        assert_eq!(main_argv, os_strings("dspbptk ./offset offset 1 0 0"));
+        assert_eq!(chained_argvs, vec![os_strings("rotate 90")]);
+        let args = Args::try_parse_from(main_argv).unwrap();
+        assert_eq!(args.input, PathBuf::from("./offset"));
+
+        // 属于编辑子命令的选项和它的值
+        let (main_argv, chained_argvs) = split(
+            "dspbptk -o out x.txt offset 1 0 0 edit-stations --item 1104 --set-max=10 --seed 1",
+        );
+        assert_eq!(
+            main_argv,
+            os_strings("dspbptk -o out x.txt offset 1 0 0 --seed 1")
+        );
+        assert_eq!(
+            chained_argvs,
+            vec![os_strings("edit-stations --item 1104 --set-max=10")]
+        );
+        let edit =
+            parse_edit_command(os_strings("edit-stations --set-max -1 --drone-range 60")).unwrap();
+        assert!(matches!(
+            edit,
+            EditCommand::EditStations(EditStationsArgs { set_max: Some(-1), drone_range: Some(range), .. })
+                if range == 60.0
+        ));
+    }
+
+    #[test]
+    fn test_parse_negative_numbers() {
+        let (main_argv, chained_argvs) =
+            split("dspbptk x.txt rotate -90 -1.5 offset 1 0 0 rotate -45");
+        let args = Args::try_parse_from(main_argv).unwrap();
+        assert!(matches!(
+            args.subcommand,
+            Some(SubCommand::Edit(EditCommand::Rotate(RotateArgs { angle, x, y: 0.0 })))
+                if angle == -90.0 && x == -1.5
+        ));
+        assert!(matches!(
+            parse_edit_command(os_strings("mirror y -2.5")),
+            Ok(EditCommand::Mirror(MirrorArgs { coordinate, .. })) if coordinate == -2.5
+        ));
+        assert!(matches!(
+            parse_edit_command(os_strings("relocate 0 0 0 -100")),
+            Ok(EditCommand::Relocate(RelocateArgs { to_y, .. })) if to_y == -100.0
+        ));
+        assert!(matches!(
+            parse_edit_command(os_strings("circular-pattern 4 -1 -2 -3")),
+            Ok(EditCommand::CircularPattern(CircularPatternArgs { latitude_shift, .. })) if latitude_shift == -1.0
+        ));
+        assert!(matches!(
+            parse_edit_command(os_strings("grid-pattern -3 2 2 2 none -10 -20")),
+            Ok(EditCommand::GridPattern(GridPatternArgs { x, origin_y, .. })) if x == -3.0 && origin_y == -20.0
+        ));
+        let edits = collect_edit_commands(&args, chained_argvs).unwrap();
+        assert!(matches!(
+            edits.last(),
+            Some(EditCommand::Rotate(RotateArgs { angle, .. })) if *angle == -45.0
+        ));
+    }
 }
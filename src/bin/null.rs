@@ -1,5 +1,8 @@
 use dspbptk::{
-    blueprint::data::{content::Content, header::Header},
+    blueprint::{
+        Version,
+        data::{content::Content, header::Header},
+    },
     error::DspbptkError,
     workflow::{BlueprintKind, LegalBlueprintFileType, process::process_back_end},
 };
@@ -9,11 +12,13 @@ fn main() -> Result<(), DspbptkError> {
     let header_data = Header::default();
     let content_data = Content::default();
 
-    if let BlueprintKind::Txt(blueprint) = process_back_end(
+    if let (BlueprintKind::Txt(blueprint), _) = process_back_end(
         &header_data,
         &content_data,
         &zopfli_options,
         &LegalBlueprintFileType::Txt,
+        &Version::Neg101,
+        false,
     )? {
         print!("{blueprint}");
     }
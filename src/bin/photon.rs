@@ -1,10 +1,13 @@
 use dspbptk::{
-    blueprint::data::{content::Content, header::Header},
+    blueprint::{
+        Version,
+        data::{content::Content, header::Header},
+    },
     dspbptk_blueprint::{
         Building,
         editor::{belt::connect_belts, fix_uuid::fix_dspbptk_buildings_index},
         generator::tesselation::{Module, module::receiver_1i1o},
-        uuid::some_new_uuid as new_some_uuid,
+        uuid::UuidAllocator,
     },
     error::DspbptkError::{self, UnexpectBuildingsCount},
     item::Item,
@@ -24,6 +27,8 @@ struct Row {
 // 当error=0时，期望输出2920锅；然后在锅不减少的情况下试出最大的error(0.00019, 0.00020)
 // 考虑行星尺寸与IEEE754标准，至少要让ERROR > 2^-15 (约0.00003)
 const ERROR: f64 = 0.00019;
+// uuid的种子，相同的种子总是生成相同的蓝图
+const SEED: u64 = 0;
 // 锅的尺寸数据由src/bin/test_ray_receiver_size测出
 const GRID_Y: f64 = receiver_1i1o::GRID_Y + ERROR;
 const GRID_X: f64 = receiver_1i1o::GRID_X + ERROR;
@@ -94,6 +99,7 @@ fn receivers_with_io(
     row: &Row,
     lens_belts: &[Building],
     photons_belts: &[Building],
+    uuids: &mut UuidAllocator,
 ) -> Vec<Building> {
     (0..row.n)
         .map(|i| {
@@ -118,13 +124,14 @@ fn receivers_with_io(
                 -1,
                 nearest_photons_belt,
                 output_to_slot,
+                uuids,
             )
         })
         .collect::<Vec<_>>()
         .concat()
 }
 
-fn main_belts(row: &Row) -> Vec<Building> {
+fn main_belts(row: &Row, uuids: &mut UuidAllocator) -> Vec<Building> {
     const BELT_GRID: f64 = 1.83;
     const BELT_ARC: f64 = arc_from_grid(BELT_GRID);
 
@@ -138,7 +145,7 @@ fn main_belts(row: &Row) -> Vec<Building> {
 
     (0..=belts_count)
         .map(|i| Building {
-            uuid: new_some_uuid(),
+            uuid: uuids.some_new_uuid(),
             item_id: Item::极速传送带 as i16,
             model_index: Item::极速传送带.model().default_value(),
             local_offset: Vector3::new(
@@ -151,12 +158,12 @@ fn main_belts(row: &Row) -> Vec<Building> {
         .collect::<Vec<_>>()
 }
 
-fn layout_to_buildings(rows: &[Row]) -> Vec<Building> {
+fn layout_to_buildings(rows: &[Row], uuids: &mut UuidAllocator) -> Vec<Building> {
     // 生成主干传送带
     let belts_in_rows = rows
         .iter()
         .map(|row| {
-            let row_of_belt = main_belts(row);
+            let row_of_belt = main_belts(row, uuids);
             connect_belts(&row_of_belt, None, 0, None, 0)
         })
         .collect::<Vec<_>>();
@@ -172,7 +179,7 @@ fn layout_to_buildings(rows: &[Row]) -> Vec<Building> {
             } else {
                 (&belts_in_rows[i], &belts_in_rows[i + 1])
             };
-            receivers_with_io(row, lens_belts, photons_belts)
+            receivers_with_io(row, lens_belts, photons_belts, uuids)
         })
         .collect::<Vec<_>>();
 
@@ -181,7 +188,11 @@ fn layout_to_buildings(rows: &[Row]) -> Vec<Building> {
 
     let all_buildings = all_buildings_in_rows.concat();
 
-    fix_dspbptk_buildings_index(all_buildings)
+    let (buildings, warns) = fix_dspbptk_buildings_index(all_buildings);
+    for warn in warns {
+        log::warn!("{warn}");
+    }
+    buildings
 }
 
 fn main() -> Result<(), DspbptkError> {
@@ -194,7 +205,7 @@ fn main() -> Result<(), DspbptkError> {
     let rows = calculate_layout();
 
     // 再转换为建筑列表
-    let buildings = layout_to_buildings(&rows);
+    let buildings = layout_to_buildings(&rows, &mut UuidAllocator::from_seed(SEED));
 
     let content_data = Content {
         buildings_length: u32::try_from(buildings.len()).map_err(UnexpectBuildingsCount)?,
@@ -205,11 +216,13 @@ fn main() -> Result<(), DspbptkError> {
         ..Default::default()
     };
 
-    if let BlueprintKind::Txt(blueprint) = process_back_end(
+    if let (BlueprintKind::Txt(blueprint), _) = process_back_end(
         &header_data,
         &content_data,
         &zopfli_options,
         &LegalBlueprintFileType::Txt,
+        &Version::Neg101,
+        false,
     )? {
         // cargo run --bin photon --release > "C:\Users\%USERNAME%\Documents\Dyson Sphere Program\Blueprint\receiver2920.txt"
         print!("{blueprint}");
@@ -1,10 +1,13 @@
 use dspbptk::{
-    blueprint::data::{content::Content, header::Header},
+    blueprint::{
+        Version,
+        data::{content::Content, header::Header},
+    },
     dspbptk_blueprint::{
         Building,
         editor::fix_uuid::fix_dspbptk_buildings_index,
         generator::tesselation::{Draft, Module},
-        uuid::some_new_uuid,
+        uuid::UuidAllocator,
     },
     error::DspbptkError::{self, UnexpectBuildingsCount},
     item::Item,
@@ -13,9 +16,9 @@ use dspbptk::{
 };
 use nalgebra::Vector3;
 
-fn new_pv(local_offset: Vector3<f64>) -> Building {
+fn new_pv(local_offset: Vector3<f64>, uuids: &mut UuidAllocator) -> Building {
     Building {
-        uuid: some_new_uuid(),
+        uuid: uuids.some_new_uuid(),
         item_id: Item::太阳能板 as i16,
         model_index: Item::太阳能板.model().default_value(),
         local_offset,
@@ -25,8 +28,10 @@ fn new_pv(local_offset: Vector3<f64>) -> Building {
 }
 
 const ERROR: f64 = 0.00000;
+// uuid的种子，相同的种子总是生成相同的蓝图
+const SEED: u64 = 0;
 
-fn layout_to_buildings(layout: &Draft) -> Vec<Building> {
+fn layout_to_buildings(layout: &Draft, uuids: &mut UuidAllocator) -> Vec<Building> {
     let mut buildings = Vec::new();
 
     for row in &layout.rows {
@@ -36,7 +41,7 @@ fn layout_to_buildings(layout: &Draft) -> Vec<Building> {
                 grid_from_arc(row.top_y - 0.5 * row.module_type.arc_y),
                 0.0,
             );
-            buildings.push(new_pv(local_offset));
+            buildings.push(new_pv(local_offset, uuids));
         }
     }
 
@@ -55,7 +60,13 @@ fn main() -> Result<(), DspbptkError> {
     while layout.push(module.clone()) {}
     dbg!(layout.rows.len());
 
-    let buildings = fix_dspbptk_buildings_index(layout_to_buildings(&layout));
+    let (buildings, warns) = fix_dspbptk_buildings_index(layout_to_buildings(
+        &layout,
+        &mut UuidAllocator::from_seed(SEED),
+    ));
+    for warn in warns {
+        log::warn!("{warn}");
+    }
 
     let content_data = Content {
         buildings_length: u32::try_from(buildings.len()).map_err(UnexpectBuildingsCount)?,
@@ -66,11 +77,13 @@ fn main() -> Result<(), DspbptkError> {
         ..Default::default()
     };
 
-    if let BlueprintKind::Txt(blueprint) = process_back_end(
+    if let (BlueprintKind::Txt(blueprint), _) = process_back_end(
         &header_data,
         &content_data,
         &zopfli_options,
         &LegalBlueprintFileType::Txt,
+        &Version::Neg101,
+        false,
     )? {
         // cargo run --bin pv --release > "C:\Users\%USERNAME%\Documents\Dyson Sphere Program\Blueprint\pv.txt"
         print!("{blueprint}");
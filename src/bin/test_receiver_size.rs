@@ -1,7 +1,10 @@
 use dspbptk::{
-    blueprint::data::{content::Content, header::Header},
+    blueprint::{
+        Version,
+        data::{content::Content, header::Header},
+    },
     dspbptk_blueprint::{
-        Building, editor::fix_uuid::fix_dspbptk_buildings_index, uuid::some_new_uuid,
+        Building, editor::fix_uuid::fix_dspbptk_buildings_index, uuid::UuidAllocator,
     },
     error::DspbptkError::{self},
     item::Item,
@@ -9,9 +12,9 @@ use dspbptk::{
 };
 use nalgebra::Vector3;
 
-fn new_receiver(local_offset: Vector3<f64>) -> Building {
+fn new_receiver(local_offset: Vector3<f64>, uuids: &mut UuidAllocator) -> Building {
     Building {
-        uuid: some_new_uuid(),
+        uuid: uuids.some_new_uuid(),
         item_id: Item::射线接收站 as i16,
         model_index: Item::射线接收站.model().default_value(),
         local_offset,
@@ -23,37 +26,47 @@ fn new_receiver(local_offset: Vector3<f64>) -> Building {
 fn main() -> Result<(), DspbptkError> {
     let header_data = Header::default();
     let zopfli_options = zopfli::Options::default();
+    let mut uuids = UuidAllocator::from_seed(0);
 
     // 基础行
     let base = (0..=9)
-        .map(|x| new_receiver(Vector3::new(15.0 * f64::from(x), 0.0, 0.0)))
+        .map(|x| new_receiver(Vector3::new(15.0 * f64::from(x), 0.0, 0.0), &mut uuids))
         .collect::<Vec<_>>();
 
     // 测试长轴碰撞
     let test_axis = (0..=9)
         .map(|x| {
-            new_receiver(Vector3::new(
-                15.0 * f64::from(x),
-                f64::from(x).mul_add(0.00001, 7.3072),
-                0.0,
-            ))
+            new_receiver(
+                Vector3::new(
+                    15.0 * f64::from(x),
+                    f64::from(x).mul_add(0.00001, 7.3072),
+                    0.0,
+                ),
+                &mut uuids,
+            )
         })
         .collect(); // (7.30725, 7.30726)
 
     // 测试角落碰撞
     let test_corner = (0..=9)
         .map(|x| {
-            new_receiver(Vector3::new(
-                f64::from(x).mul_add(15.0, 7.2),
-                f64::from(x).mul_add(-0.00001, -4.1982),
-                0.0,
-            ))
+            new_receiver(
+                Vector3::new(
+                    f64::from(x).mul_add(15.0, 7.2),
+                    f64::from(x).mul_add(-0.00001, -4.1982),
+                    0.0,
+                ),
+                &mut uuids,
+            )
         })
         .collect(); // (4.19828, 4.19829)
 
     // 拼接所有建筑
     let buildings = [base, test_axis, test_corner].concat();
-    let buildings = fix_dspbptk_buildings_index(buildings);
+    let (buildings, warns) = fix_dspbptk_buildings_index(buildings);
+    for warn in warns {
+        log::warn!("{warn}");
+    }
 
     let content_data = Content {
         buildings_length: u32::try_from(buildings.len()).unwrap(),
@@ -66,11 +79,13 @@ fn main() -> Result<(), DspbptkError> {
 
     println!("{content_data:#?}");
 
-    if let BlueprintKind::Txt(blueprint) = process_back_end(
+    if let (BlueprintKind::Txt(blueprint), _) = process_back_end(
         &header_data,
         &content_data,
         &zopfli_options,
         &LegalBlueprintFileType::Txt,
+        &Version::Neg101,
+        false,
     )? {
         print!("{blueprint}");
     }
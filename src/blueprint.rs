@@ -7,3 +7,4 @@ pub use data::content::area::Area;
 pub use data::content::building::Building;
 pub use data::content::building::Version;
 pub use data::header::Header;
+pub use data::header::typed::TypedHeader;
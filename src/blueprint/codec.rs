@@ -6,7 +6,7 @@ use crate::{
     blueprint::{codec::md5f::compute_md5f_string, data::Blueprint},
     error::{
         DspbptkError::{self, BrokenBlueprint},
-        DspbptkWarn::{self, FewUnknownAfterBlueprint, LotUnknownAfterBlueprint},
+        DspbptkWarn::{self, FewUnknownAfterBlueprint, LotUnknownAfterBlueprint, UnexpectedMD5F},
     },
 };
 use nom::{
@@ -44,6 +44,17 @@ fn parse_non_finish(string: &'_ str) -> IResult<&'_ str, Blueprint<'_>> {
     ))
 }
 
+/// 重新计算蓝图的MD5F，与蓝图中记录的MD5F不一致时返回警告
+#[must_use]
+pub fn verify_md5f(data: &Blueprint) -> Option<DspbptkWarn> {
+    let expected = compute_md5f_string(&format!("{}\"{}", data.header, data.content));
+    if expected.eq_ignore_ascii_case(data.md5f) {
+        None
+    } else {
+        Some(UnexpectedMD5F(expected, data.md5f.to_string()))
+    }
+}
+
 /// # Errors
 /// 可能的原因：
 /// * 蓝图已损坏，或者编码不受支持
@@ -52,11 +63,15 @@ pub fn parse(string: &'_ str) -> Result<(Blueprint<'_>, Vec<DspbptkWarn>), Dspbp
         .finish()
         .map_err(|e| BrokenBlueprint(e.clone().into()))?;
     let unknown_length = unknown.len();
-    let warns = match unknown.len() {
+    let warns_unknown = match unknown.len() {
         10.. => vec![LotUnknownAfterBlueprint(unknown_length)],
         1..=9 => vec![FewUnknownAfterBlueprint(unknown.to_string())],
         _ => Vec::new(),
     };
+    let warns = verify_md5f(&data)
+        .into_iter()
+        .chain(warns_unknown)
+        .collect();
     Ok((data, warns))
 }
 
@@ -69,9 +84,12 @@ pub fn serialization(header: &str, content: &str) -> String {
     header_content
 }
 
+#[allow(clippy::unwrap_used)]
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::blueprint::{Building, Version, data::content::Content};
+    use std::num::NonZero;
 
     #[test]
     fn test_parse() {
@@ -92,6 +110,20 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_parse_unexpected_md5f() {
+        let string = "BLUEPRINT:0,0,0,0,0,0,0,0,0,0.0.0.0,,\"H4sIAAAAAAAAA2NkQAWMUMyARCMBANjTKTsvAAAA\"00000000000000000000000000000000";
+        let result = parse(string).map(|(_, warns)| warns);
+
+        assert_eq!(
+            result.ok(),
+            Some(vec![UnexpectedMD5F(
+                "E4E5A1CF28F1EC611E33498CBD0DF02B".to_string(),
+                "00000000000000000000000000000000".to_string()
+            )])
+        );
+    }
+
     #[test]
     fn test_serialization() {
         let blueprint_data = Blueprint {
@@ -105,4 +137,60 @@ mod test {
             "BLUEPRINT:0,0,0,0,0,0,0,0,0,0.0.0.0,,\"H4sIAAAAAAAAA2NkQAWMUMyARCMBANjTKTsvAAAA\"E4E5A1CF28F1EC611E33498CBD0DF02B"
         );
     }
+
+    #[test]
+    fn test_round_trip_blueprint() {
+        let zopfli_options = zopfli::Options {
+            iteration_count: NonZero::new(1).unwrap(),
+            ..Default::default()
+        };
+        // header末尾有多余的字段，content末尾有多余的字节
+        let header_expected = "BLUEPRINT:0,9,0,1,2,3,4,0,5,6.7.8.9,a,b,c,42";
+        let bin_expected = [
+            Content {
+                buildings_length: 1,
+                buildings: vec![Building {
+                    item_id: 2303,
+                    model_index: 65,
+                    local_offset_x: 1.5,
+                    yaw: 90.0,
+                    recipe_id: 1,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }
+            .to_bin(&Version::Neg101, false),
+            vec![1, 2, 3],
+        ]
+        .concat();
+        let (content, _) = Content::from_bin(&bin_expected).unwrap();
+        let string = serialization(
+            header_expected,
+            &content::string_from_data(&content, &zopfli_options, &Version::Neg101, true).unwrap(),
+        );
+
+        let (data, warns) = parse(&string).unwrap();
+        assert!(warns.is_empty());
+        let (header_data, _) = header::parse(data.header).unwrap();
+        let (content_data, _) =
+            Content::from_bin(&content::bin_from_string(data.content).unwrap()).unwrap();
+        let string_out = serialization(
+            &header::serialization(&header_data, true),
+            &content::string_from_data(&content_data, &zopfli_options, &Version::Neg101, true)
+                .unwrap(),
+        );
+
+        let (data_out, warns_out) = parse(&string_out).unwrap();
+        assert!(warns_out.is_empty());
+        assert_eq!(data_out.header, header_expected);
+        assert_eq!(
+            content::bin_from_string(data_out.content).unwrap(),
+            bin_expected
+        );
+        assert_eq!(
+            data_out.md5f,
+            compute_md5f_string(&format!("{}\"{}", data_out.header, data_out.content))
+        );
+        assert_eq!(string_out, string);
+    }
 }
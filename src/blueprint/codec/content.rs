@@ -9,13 +9,13 @@ use crate::{
     },
 };
 use base64::prelude::*;
-use flate2::read::GzDecoder;
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
 use nom::{
     Finish, IResult, Parser,
     multi::count,
     number::complete::{le_i32, le_u8, le_u32},
 };
-use std::io::Read;
+use std::io::{Read, Write};
 
 impl Content {
     /// # Errors
@@ -34,9 +34,19 @@ impl Content {
         Ok((content, warns))
     }
 
+    /// 检查所有建筑能否以指定版本无损序列化
+    #[must_use]
+    pub fn check_version(&self, version: &Version) -> Vec<DspbptkWarn> {
+        self.buildings
+            .iter()
+            .filter_map(|building_data| building::check_version(building_data, version))
+            .collect()
+    }
+
+    /// `preserve_unknown`为真时，原样写回content末尾的未知数据
     // TODO 性能优化，当前实现存在多次数组拓容。
     #[must_use]
-    pub fn to_bin(&self) -> Vec<u8> {
+    pub fn to_bin(&self, version: &Version, preserve_unknown: bool) -> Vec<u8> {
         let mut bin = Vec::new();
         bin.extend_from_slice(&self.patch.to_le_bytes());
         bin.extend_from_slice(&self.cursor_offset_x.to_le_bytes());
@@ -49,8 +59,11 @@ impl Content {
         bin = self.areas.iter().fold(bin, area::serialization);
         bin.extend_from_slice(&self.buildings_length.to_le_bytes());
         bin = self.buildings.iter().fold(bin, |bin, building_data| {
-            building::serialization(bin, building_data, &Version::Neg101)
+            building::serialization(bin, building_data, version)
         });
+        if preserve_unknown {
+            bin.extend_from_slice(&self.unknown);
+        }
         bin
     }
 }
@@ -138,8 +151,51 @@ pub fn bin_from_string(string: &str) -> Result<Vec<u8>, DspbptkError> {
 pub fn string_from_data(
     data: &Content,
     zopfli_options: &zopfli::Options,
+    version: &Version,
+    preserve_unknown: bool,
 ) -> Result<String, DspbptkError> {
-    let bin = data.to_bin();
+    let bin = data.to_bin(version, preserve_unknown);
     let gzip = gzip_from_bin(&bin, zopfli_options)?;
     Ok(string_from_gzip(&gzip))
 }
+
+/// 用flate2快速估计content编码后的base64长度，比zopfli快得多，但结果通常略大
+///
+/// # Errors
+/// 可能的原因：
+/// * gzip压缩错误，这个错误通常不该出现
+pub fn estimate_string_length(
+    data: &Content,
+    version: &Version,
+    preserve_unknown: bool,
+) -> Result<usize, DspbptkError> {
+    let bin = data.to_bin(version, preserve_unknown);
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&bin).map_err(CanNotCompressGzip)?;
+    let gzip = encoder.finish().map_err(CanNotCompressGzip)?;
+    Ok(gzip.len().div_ceil(3) * 4)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_unknown_after_content() {
+        let bin_expected = [
+            Content::default().to_bin(&Version::Neg101, false),
+            vec![1, 2, 3],
+        ]
+        .concat();
+
+        let (content, warns) = Content::from_bin(&bin_expected).unwrap();
+
+        assert_eq!(warns, vec![FewUnknownAfterContent(vec![1, 2, 3])]);
+        assert_eq!(content.to_bin(&Version::Neg101, true), bin_expected);
+        assert_eq!(
+            content.to_bin(&Version::Neg101, false),
+            Content::default().to_bin(&Version::Neg101, false)
+        );
+    }
+}
@@ -2,9 +2,12 @@ mod v0;
 mod v100;
 mod v101;
 
-use crate::blueprint::{
-    Building,
-    Version::{self, Neg100, Neg101, Zero},
+use crate::{
+    blueprint::{
+        Building,
+        Version::{self, Neg100, Neg101, Zero},
+    },
+    error::DspbptkWarn::{self, LossyLegacyVersion},
 };
 use nom::{IResult, Parser, branch::alt};
 
@@ -29,3 +32,48 @@ pub fn serialization(bin: Vec<u8>, data: &Building, version: &Version) -> Vec<u8
         Neg101 => v101::serialization(bin, data),
     }
 }
+
+/// 检查建筑能否以指定版本无损序列化，旧版本的建筑格式不储存部分角度数据
+#[must_use]
+pub fn check_version(data: &Building, version: &Version) -> Option<DspbptkWarn> {
+    let lossless = match version {
+        Zero => [data.tilt, data.pitch, data.tilt2, data.pitch2]
+            .iter()
+            .all(|x| *x == 0.0),
+        Neg100 => [data.pitch, data.tilt2, data.pitch2]
+            .iter()
+            .all(|x| *x == 0.0),
+        Neg101 => true,
+    };
+    if lossless {
+        None
+    } else {
+        Some(LossyLegacyVersion(data.index, data.item_id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_version() {
+        let belt = Building {
+            item_id: 2001,
+            tilt: 7.1,
+            tilt2: 7.1,
+            ..Default::default()
+        };
+
+        assert_eq!(check_version(&belt, &Neg101), None);
+        assert_eq!(
+            check_version(&belt, &Neg100),
+            Some(LossyLegacyVersion(Building::INDEX_NULL, 2001))
+        );
+        assert_eq!(
+            check_version(&belt, &Zero),
+            Some(LossyLegacyVersion(Building::INDEX_NULL, 2001))
+        );
+        assert_eq!(check_version(&Building::default(), &Zero), None);
+    }
+}
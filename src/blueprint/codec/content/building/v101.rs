@@ -1,4 +1,7 @@
-use crate::blueprint::{Building, Version::Neg101};
+use crate::{
+    blueprint::{Building, Version::Neg101},
+    item::BuildingKind,
+};
 use nom::{
     IResult, Parser,
     bytes::complete::tag,
@@ -6,11 +9,6 @@ use nom::{
     number::complete::{le_f32, le_i8, le_i16, le_i32, le_u16},
 };
 
-const BELT_LOW: i16 = 2001;
-const BELT_HIGH: i16 = 2009;
-const SORTER_LOW: i16 = 2011;
-const SORTER_HIGH: i16 = 2019;
-
 // 定义过于复杂的类型，避免使用时不小心写错
 type F32x12 = (f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32);
 
@@ -40,9 +38,9 @@ pub fn deserialization(bin: &[u8]) -> IResult<&[u8], Building> {
             tilt2,
             pitch2,
         ),
-    ) = match item_id {
-        SORTER_LOW..=SORTER_HIGH => parse_sorter(unknown)?,
-        BELT_LOW..=BELT_HIGH => parse_belt(unknown)?,
+    ) = match BuildingKind::from_item_id(item_id) {
+        BuildingKind::Sorter => parse_sorter(unknown)?,
+        BuildingKind::Belt => parse_belt(unknown)?,
         _ => parse_default(unknown)?,
     };
 
@@ -101,8 +99,8 @@ pub fn serialization(mut bin: Vec<u8>, data: &Building) -> Vec<u8> {
     bin.extend_from_slice(&data.model_index.to_le_bytes());
     bin.extend_from_slice(&data.area_index.to_le_bytes());
 
-    match data.item_id {
-        SORTER_LOW..=SORTER_HIGH => {
+    match data.kind() {
+        BuildingKind::Sorter => {
             bin.extend_from_slice(&data.local_offset_x.to_le_bytes());
             bin.extend_from_slice(&data.local_offset_y.to_le_bytes());
             bin.extend_from_slice(&data.local_offset_z.to_le_bytes());
@@ -116,7 +114,7 @@ pub fn serialization(mut bin: Vec<u8>, data: &Building) -> Vec<u8> {
             bin.extend_from_slice(&data.tilt2.to_le_bytes());
             bin.extend_from_slice(&data.pitch2.to_le_bytes());
         }
-        BELT_LOW..=BELT_HIGH => {
+        BuildingKind::Belt => {
             bin.extend_from_slice(&data.local_offset_x.to_le_bytes());
             bin.extend_from_slice(&data.local_offset_y.to_le_bytes());
             bin.extend_from_slice(&data.local_offset_z.to_le_bytes());
@@ -508,4 +506,44 @@ mod test {
 
         assert_eq!(test, Ok(([].as_slice(), data_expected)));
     }
+
+    #[test]
+    fn test_round_trip_version_neg101_unknown_item_id() {
+        // 2005和2015是未知的传送带和分拣器，9999是未知的建筑
+        for item_id in [2005, 2015, 9999] {
+            let data = Building {
+                index: 1,
+                item_id,
+                yaw: 6.1,
+                tilt: 7.1,
+                ..Default::default()
+            };
+
+            let bin_expected = serialization(Vec::new(), &data);
+            let (unknown, test) = deserialization(&bin_expected).finish().unwrap();
+
+            assert!(unknown.is_empty());
+            assert_eq!(test.item_id, item_id);
+            assert_eq!(serialization(Vec::new(), &test), bin_expected);
+        }
+        // 未知的传送带和分拣器按传送带和分拣器编码，保留了tilt
+        assert_eq!(
+            serialization(
+                Vec::new(),
+                &Building {
+                    item_id: 2005,
+                    ..Default::default()
+                }
+            )
+            .len(),
+            serialization(
+                Vec::new(),
+                &Building {
+                    item_id: 2001,
+                    ..Default::default()
+                }
+            )
+            .len()
+        );
+    }
 }
@@ -1,3 +1,5 @@
+pub mod url;
+
 use crate::{
     blueprint::Header,
     error::{
@@ -72,10 +74,11 @@ pub fn parse(string: &'_ str) -> Result<(Header, Vec<DspbptkWarn>), DspbptkError
     }
 }
 
+/// `preserve_unknown`为真时，原样写回header末尾的未知数据
 #[must_use]
-pub fn serialization(data: &Header) -> String {
+pub fn serialization(data: &Header, preserve_unknown: bool) -> String {
     format!(
-        "BLUEPRINT:0,{},{},{},{},{},{},0,{},{},{},{}",
+        "BLUEPRINT:0,{},{},{},{},{},{},0,{},{},{},{}{}",
         data.layout,
         data.icons_0,
         data.icons_1,
@@ -86,6 +89,7 @@ pub fn serialization(data: &Header) -> String {
         data.game_version,
         data.short_desc,
         data.desc,
+        if preserve_unknown { &data.unknown } else { "" },
     )
 }
 
@@ -138,8 +142,30 @@ mod test {
         };
 
         assert_eq!(
-            serialization(&header),
+            serialization(&header, false),
             "BLUEPRINT:0,9,0,1,2,3,4,0,5,6.7.8.9,,"
         );
     }
+
+    #[test]
+    fn test_round_trip_unknown_after_header() {
+        let string = "BLUEPRINT:0,9,0,1,2,3,4,0,5,6.7.8.9,a,b,c,42";
+
+        let result = parse(string).map(|(header, warns)| {
+            (
+                serialization(&header, true),
+                serialization(&header, false),
+                warns,
+            )
+        });
+
+        assert_eq!(
+            result.ok(),
+            Some((
+                string.to_string(),
+                "BLUEPRINT:0,9,0,1,2,3,4,0,5,6.7.8.9,a,b".to_string(),
+                vec![UnknownAfterHeader]
+            ))
+        );
+    }
 }
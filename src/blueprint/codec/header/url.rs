@@ -0,0 +1,82 @@
+use crate::error::DspbptkError::{self, BrokenUrlEncoding};
+
+/// RFC 3986中的非保留字符，编码时原样输出
+const fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+/// 解码游戏中经过百分号编码的文本，如`short_desc`和`desc`
+///
+/// # Errors
+/// 可能的原因：
+/// * 百分号后面不是两位十六进制数
+/// * 解码结果不是合法的UTF-8
+pub fn decode(string: &str) -> Result<String, DspbptkError> {
+    let error = || BrokenUrlEncoding(string.to_string());
+
+    let mut bytes = Vec::with_capacity(string.len());
+    let mut iter = string.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next(), iter.next()];
+            let [Some(high), Some(low)] = hex else {
+                return Err(error());
+            };
+            let hex = std::str::from_utf8(&[high, low])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(error)?;
+            bytes.push(hex);
+        } else {
+            bytes.push(byte);
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| error())
+}
+
+/// 以与游戏相同的方式（C#的`Uri.EscapeDataString`）进行百分号编码\
+/// 编码结果中不会出现逗号，因此不会破坏header的结构
+#[must_use]
+pub fn encode(string: &str) -> String {
+    string
+        .bytes()
+        .map(|byte| {
+            if is_unreserved(byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode("光子锅,测试"),
+            "%E5%85%89%E5%AD%90%E9%94%85%2C%E6%B5%8B%E8%AF%95"
+        );
+        assert_eq!(encode("a b+c/d~e_f.g-h!"), "a%20b%2Bc%2Fd~e_f.g-h%21");
+        assert_eq!(encode(""), "");
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode("%E5%85%89%E5%AD%90%E9%94%85%2C%E6%B5%8B%E8%AF%95").ok(),
+            Some("光子锅,测试".to_string())
+        );
+        assert_eq!(
+            decode("a%20b%2Bc%2Fd~e_f.g-h%21").ok(),
+            Some("a b+c/d~e_f.g-h!".to_string())
+        );
+        assert!(decode("%E5%85").is_err());
+        assert!(decode("%2").is_err());
+        assert!(decode("%zz").is_err());
+    }
+}
@@ -1,7 +1,13 @@
+use crate::{
+    error::DspbptkError,
+    item::BuildingKind,
+    parameters::{ParameterView, read_parameters, write_parameters},
+};
+use clap::ValueEnum;
 use num_enum::IntoPrimitive;
 use strum_macros::EnumIter;
 
-#[derive(IntoPrimitive, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoPrimitive, EnumIter, ValueEnum)]
 #[repr(i32)]
 pub enum Version {
     Zero = 0,
@@ -43,6 +49,37 @@ pub struct Building {
 
 impl Building {
     pub const INDEX_NULL: i32 = -1;
+
+    /// 建筑类型，见[`BuildingKind::from_item_id`]
+    #[must_use]
+    pub fn kind(&self) -> BuildingKind {
+        BuildingKind::from_item_id(self.item_id)
+    }
+
+    /// 以类型化视图读取参数
+    ///
+    /// # Errors
+    /// 可能的原因：
+    /// * 建筑类型与视图不符
+    pub fn parameters_view<T: ParameterView>(&self) -> Result<T, DspbptkError> {
+        read_parameters(self.item_id, &self.parameters)
+    }
+
+    /// 把类型化视图写回参数，同时更新参数长度
+    ///
+    /// # Errors
+    /// 可能的原因：
+    /// * 建筑类型与视图不符
+    /// * 参数太长
+    pub fn with_parameters_view<T: ParameterView>(self, view: &T) -> Result<Self, DspbptkError> {
+        let parameters = write_parameters(self.item_id, view, self.parameters)?;
+        Ok(Self {
+            parameters_length: u16::try_from(parameters.len())
+                .map_err(DspbptkError::UnexpectParametersLength)?,
+            parameters,
+            ..self
+        })
+    }
 }
 
 impl Default for Building {
@@ -1,3 +1,5 @@
+pub mod typed;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Header {
     pub layout: String,
@@ -0,0 +1,370 @@
+use crate::{
+    blueprint::{Header, codec::header::url},
+    error::DspbptkError::{self, UnexpectHeaderField},
+    item::Item,
+};
+use num_enum::{FromPrimitive, IntoPrimitive};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+/// 蓝图图标的排列方式，未知的排列方式原样保存在`Unknown`中
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[repr(i32)]
+pub enum Layout {
+    None = 0,
+    OneIcon = 1,
+    OneIconSmall = 2,
+    TwoIcon46 = 10,
+    TwoIcon53 = 11,
+    TwoIcon59 = 12,
+    TwoIcon57 = 13,
+    TwoIcon51 = 14,
+    ThreeIcon813 = 20,
+    ThreeIcon279 = 21,
+    ThreeIcon573 = 22,
+    ThreeIcon591 = 23,
+    FourIcon7913 = 30,
+    FourIcon8462 = 31,
+    FiveIcon57913 = 40,
+    FiveIconPenta = 41,
+    #[num_enum(catch_all)]
+    Unknown(i32),
+}
+
+/// 蓝图图标，即游戏中的信号id；物品以外的信号（数字、字母、配方、科技等）原样保存在`Signal`中
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Icon {
+    None,
+    Item(Item),
+    Signal(i32),
+}
+
+impl From<i32> for Icon {
+    fn from(id: i32) -> Self {
+        match id {
+            0 => Self::None,
+            _ => i16::try_from(id)
+                .ok()
+                .and_then(|id| Item::try_from(id).ok())
+                .map_or(Self::Signal(id), Self::Item),
+        }
+    }
+}
+
+impl From<Icon> for i32 {
+    fn from(icon: Icon) -> Self {
+        match icon {
+            Icon::None => 0,
+            Icon::Item(item) => Self::from(i16::from(item)),
+            Icon::Signal(id) => id,
+        }
+    }
+}
+
+/// .NET的`DateTime.Ticks`，即从0001-01-01 00:00:00起经过的100纳秒数\
+/// 游戏记录的是本地时间，不包含时区信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Ticks(pub i64);
+
+impl Ticks {
+    /// 1970-01-01 00:00:00对应的`Ticks`
+    pub const UNIX_EPOCH: i64 = 621_355_968_000_000_000;
+
+    /// 每秒对应的`Ticks`
+    pub const PER_SECOND: i64 = 10_000_000;
+
+    /// 把`Ticks`视为UTC时间转换为`SystemTime`，超出`SystemTime`的表示范围时返回`None`
+    #[must_use]
+    pub fn to_system_time(self) -> Option<SystemTime> {
+        let delta = self.0.checked_sub(Self::UNIX_EPOCH)?;
+        let duration = Duration::from_nanos(delta.unsigned_abs().checked_mul(100)?);
+        if delta >= 0 {
+            SystemTime::UNIX_EPOCH.checked_add(duration)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_sub(duration)
+        }
+    }
+
+    /// 把`SystemTime`转换为`Ticks`，不足100纳秒的部分被舍去，超出`Ticks`的表示范围时返回`None`
+    #[must_use]
+    pub fn from_system_time(time: SystemTime) -> Option<Self> {
+        let (duration, sign) = match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => (duration, 1),
+            Err(e) => (e.duration(), -1),
+        };
+        let delta = i64::try_from(duration.as_nanos() / 100).ok()?;
+        Self::UNIX_EPOCH.checked_add(sign * delta).map(Self)
+    }
+}
+
+/// 以`YYYY-MM-DD hh:mm:ss`格式输出，不足1秒的部分被舍去
+impl Display for Ticks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const SECONDS_PER_DAY: i64 = 86400;
+        // 0001-01-01到1970-01-01的天数
+        const DAYS_BEFORE_UNIX_EPOCH: i64 = 719_162;
+
+        let seconds = self.0.div_euclid(Self::PER_SECOND);
+        let days = seconds.div_euclid(SECONDS_PER_DAY) - DAYS_BEFORE_UNIX_EPOCH;
+        let seconds_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+            seconds_of_day / 3600,
+            seconds_of_day / 60 % 60,
+            seconds_of_day % 60
+        )
+    }
+}
+
+/// 把1970-01-01起的天数转换为公历日期，算法见<https://howardhinnant.github.io/date_algorithms.html>
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// 四段式的游戏版本号，如`0.10.30.22292`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GameVersion {
+    pub major: i32,
+    pub minor: i32,
+    pub release: i32,
+    pub build: i32,
+}
+
+impl FromStr for GameVersion {
+    type Err = DspbptkError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let error = || UnexpectHeaderField("game_version", string.to_string());
+        let parts = string
+            .split('.')
+            .map(|part| parse_lossless("game_version", part))
+            .collect::<Result<Vec<i32>, _>>()
+            .map_err(|_| error())?;
+        match parts.as_slice() {
+            [major, minor, release, build] => Ok(Self {
+                major: *major,
+                minor: *minor,
+                release: *release,
+                build: *build,
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.release, self.build
+        )
+    }
+}
+
+/// `Header`的强类型视图，与`Header`之间可以无损地相互转换
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedHeader {
+    pub layout: Layout,
+    pub icons: [Icon; 5],
+    pub time: Ticks,
+    /// 游戏版本为空时是`None`
+    pub game_version: Option<GameVersion>,
+    /// 解码后的文本
+    pub short_desc: String,
+    /// 解码后的文本
+    pub desc: String,
+    pub unknown: String,
+}
+
+/// 解码描述文本，并保证解码结果可以还原为完全相同的字符串
+fn decode_lossless(field: &'static str, string: &str) -> Result<String, DspbptkError> {
+    let text = url::decode(string)?;
+    if url::encode(&text) == string {
+        Ok(text)
+    } else {
+        Err(UnexpectHeaderField(field, string.to_string()))
+    }
+}
+
+/// 解析整数，并保证解析结果可以还原为完全相同的字符串
+fn parse_lossless<T: FromStr + ToString>(
+    field: &'static str,
+    string: &str,
+) -> Result<T, DspbptkError> {
+    string
+        .parse::<T>()
+        .ok()
+        .filter(|value| value.to_string() == string)
+        .ok_or_else(|| UnexpectHeaderField(field, string.to_string()))
+}
+
+impl TryFrom<&Header> for TypedHeader {
+    type Error = DspbptkError;
+
+    /// # Errors
+    /// 可能的原因：
+    /// * header中的某个字段不是规范的数字，转换后无法还原
+    /// * 描述文本的百分号编码已损坏，或者不是游戏生成的规范编码
+    fn try_from(header: &Header) -> Result<Self, Self::Error> {
+        let icon = |string: &str| parse_lossless::<i32>("icons", string).map(Icon::from);
+        Ok(Self {
+            layout: Layout::from(parse_lossless::<i32>("layout", &header.layout)?),
+            icons: [
+                icon(&header.icons_0)?,
+                icon(&header.icons_1)?,
+                icon(&header.icons_2)?,
+                icon(&header.icons_3)?,
+                icon(&header.icons_4)?,
+            ],
+            time: Ticks(parse_lossless("time", &header.time)?),
+            game_version: match header.game_version.as_str() {
+                "" => None,
+                game_version => Some(game_version.parse()?),
+            },
+            short_desc: decode_lossless("short_desc", &header.short_desc)?,
+            desc: decode_lossless("desc", &header.desc)?,
+            unknown: header.unknown.clone(),
+        })
+    }
+}
+
+impl From<&TypedHeader> for Header {
+    fn from(typed: &TypedHeader) -> Self {
+        let [icons_0, icons_1, icons_2, icons_3, icons_4] =
+            typed.icons.map(|icon| i32::from(icon).to_string());
+        Self {
+            layout: i32::from(typed.layout).to_string(),
+            icons_0,
+            icons_1,
+            icons_2,
+            icons_3,
+            icons_4,
+            time: typed.time.0.to_string(),
+            game_version: typed
+                .game_version
+                .map_or_else(String::new, |game_version| game_version.to_string()),
+            short_desc: url::encode(&typed.short_desc),
+            desc: url::encode(&typed.desc),
+            unknown: typed.unknown.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let header = Header {
+            layout: "10".to_string(),
+            icons_0: "2003".to_string(),
+            icons_1: "20101".to_string(),
+            icons_2: "0".to_string(),
+            icons_3: "0".to_string(),
+            icons_4: "0".to_string(),
+            time: "638000000000000000".to_string(),
+            game_version: "0.10.30.22292".to_string(),
+            short_desc: "%E5%85%89%E5%AD%90%E9%94%85%2C%E6%B5%8B%E8%AF%95".to_string(),
+            desc: "a%20b".to_string(),
+            unknown: String::new(),
+        };
+
+        let typed = TypedHeader::try_from(&header);
+
+        assert_eq!(
+            typed.as_ref().ok(),
+            Some(&TypedHeader {
+                layout: Layout::TwoIcon46,
+                icons: [
+                    Icon::Item(Item::极速传送带),
+                    Icon::Signal(20101),
+                    Icon::None,
+                    Icon::None,
+                    Icon::None,
+                ],
+                time: Ticks(638_000_000_000_000_000),
+                game_version: Some(GameVersion {
+                    major: 0,
+                    minor: 10,
+                    release: 30,
+                    build: 22292,
+                }),
+                short_desc: "光子锅,测试".to_string(),
+                desc: "a b".to_string(),
+                unknown: String::new(),
+            })
+        );
+        assert_eq!(typed.ok().as_ref().map(Header::from), Some(header));
+    }
+
+    #[test]
+    fn test_unknown_layout() {
+        let header = Header {
+            layout: "99".to_string(),
+            ..Default::default()
+        };
+
+        let typed = TypedHeader::try_from(&header);
+
+        assert_eq!(
+            typed.as_ref().map(|typed| typed.layout).ok(),
+            Some(Layout::Unknown(99))
+        );
+        assert_eq!(typed.ok().as_ref().map(Header::from), Some(header));
+    }
+
+    #[test]
+    fn test_non_canonical_field() {
+        let header = Header {
+            icons_0: "01".to_string(),
+            ..Default::default()
+        };
+
+        assert!(TypedHeader::try_from(&header).is_err());
+    }
+
+    #[test]
+    fn test_non_canonical_desc() {
+        let header = Header {
+            desc: "a b".to_string(),
+            ..Default::default()
+        };
+
+        assert!(TypedHeader::try_from(&header).is_err());
+    }
+
+    #[test]
+    fn test_ticks() {
+        assert_eq!(
+            Ticks(Ticks::UNIX_EPOCH).to_system_time(),
+            Some(SystemTime::UNIX_EPOCH)
+        );
+        assert_eq!(
+            Ticks::from_system_time(SystemTime::UNIX_EPOCH),
+            Some(Ticks(Ticks::UNIX_EPOCH))
+        );
+        assert_eq!(Ticks(0).to_string(), "0001-01-01 00:00:00");
+        assert_eq!(Ticks(Ticks::UNIX_EPOCH).to_string(), "1970-01-01 00:00:00");
+        assert_eq!(
+            Ticks(638_000_000_000_000_000).to_string(),
+            "2022-09-28 22:13:20"
+        );
+    }
+}
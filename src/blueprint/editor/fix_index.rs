@@ -1,27 +1,91 @@
-use crate::blueprint::Building;
+use crate::{blueprint::Building, error::DspbptkWarn, graph::Reference};
 use std::collections::HashMap;
 
+/// 把index重排为建筑在数组中的下标
+///
+/// 指向不存在的建筑的`temp_output_obj_idx`和`temp_input_obj_idx`会被置为`INDEX_NULL`，
+/// 每个被丢弃的引用以警告的形式返回，其中的index是重排前的index，便于在输入中找到对应的建筑
 #[must_use]
-pub fn fix_buildings_index(buildings: Vec<Building>) -> Vec<Building> {
+pub fn fix_buildings_index(buildings: Vec<Building>) -> (Vec<Building>, Vec<DspbptkWarn>) {
     let lut = buildings
         .iter()
         .zip(0..=i32::MAX)
         .map(|(building, index)| (building.index, index))
         .collect::<HashMap<_, _>>();
 
-    buildings
+    let fix = |index: i32| {
+        if index == Building::INDEX_NULL {
+            Some(Building::INDEX_NULL)
+        } else {
+            lut.get(&index).copied()
+        }
+    };
+
+    let mut warns = Vec::new();
+    let buildings = buildings
         .into_iter()
-        .map(|building| Building {
-            index: *lut.get(&building.index).unwrap_or(&Building::INDEX_NULL),
-            temp_output_obj_idx: lut
-                .get(&building.temp_output_obj_idx)
-                .copied()
-                .unwrap_or(Building::INDEX_NULL),
-            temp_input_obj_idx: lut
-                .get(&building.temp_input_obj_idx)
-                .copied()
-                .unwrap_or(Building::INDEX_NULL),
-            ..building
+        .map(|building| {
+            let mut dropped = |index: i32, link: Reference| {
+                fix(index).unwrap_or_else(|| {
+                    warns.push(DspbptkWarn::DroppedReference {
+                        index: building.index,
+                        item_id: building.item_id,
+                        link,
+                    });
+                    Building::INDEX_NULL
+                })
+            };
+            let temp_output_obj_idx = dropped(building.temp_output_obj_idx, Reference::Output);
+            let temp_input_obj_idx = dropped(building.temp_input_obj_idx, Reference::Input);
+            Building {
+                index: *lut.get(&building.index).unwrap_or(&Building::INDEX_NULL),
+                temp_output_obj_idx,
+                temp_input_obj_idx,
+                ..building
+            }
         })
-        .collect()
+        .collect();
+
+    (buildings, warns)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fix_buildings_index() {
+        let buildings = vec![
+            Building {
+                index: 10,
+                temp_output_obj_idx: 20,
+                ..Default::default()
+            },
+            Building {
+                index: 20,
+                item_id: 2001,
+                temp_input_obj_idx: 10,
+                temp_output_obj_idx: 30,
+                ..Default::default()
+            },
+        ];
+
+        let (buildings, warns) = fix_buildings_index(buildings);
+
+        assert_eq!(
+            buildings
+                .iter()
+                .map(|b| (b.index, b.temp_output_obj_idx, b.temp_input_obj_idx))
+                .collect::<Vec<_>>(),
+            [(0, 1, -1), (1, -1, 0)]
+        );
+        assert_eq!(
+            warns,
+            [DspbptkWarn::DroppedReference {
+                index: 20,
+                item_id: 2001,
+                link: Reference::Output,
+            }]
+        );
+    }
 }
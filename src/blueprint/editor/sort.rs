@@ -1,11 +1,49 @@
-use crate::blueprint::Building;
+use crate::{blueprint::Building, graph::ConnectionGraph};
+use clap::ValueEnum;
+use std::collections::{HashMap, HashSet};
+use strum_macros::EnumIter;
 
-// TODO 重写拓扑排序，需要考虑更多建筑，不只是传送带
+/// 建筑的排序策略，不同的蓝图适合不同的策略，见[`sort_buildings_by`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, ValueEnum)]
+pub enum SortStrategy {
+    /// 保持原有顺序
+    Original,
+    /// 按建筑类型和坐标排序
+    Key,
+    /// 按建筑类型和坐标排序后倒序
+    KeyReversed,
+    /// 按拓扑关系排序
+    Topological,
+    /// 按拓扑关系排序后倒序，与[`sort_buildings`]相同
+    #[default]
+    TopologicalReversed,
+    /// 只按坐标排序，忽略建筑类型
+    Position,
+}
+
+/// 按指定的策略排序建筑，只改变顺序，不修改任何数据
+#[must_use]
+pub fn sort_buildings_by(buildings: Vec<Building>, strategy: SortStrategy) -> Vec<Building> {
+    match strategy {
+        SortStrategy::Original => buildings,
+        SortStrategy::Key => stable_sort_by_building_key(buildings),
+        SortStrategy::KeyReversed => {
+            let mut sorted = stable_sort_by_building_key(buildings);
+            sorted.reverse();
+            sorted
+        }
+        SortStrategy::Topological => sort_buildings(buildings, false),
+        SortStrategy::TopologicalReversed => sort_buildings(buildings, true),
+        SortStrategy::Position => stable_sort_by_position(buildings),
+    }
+}
 
 /// 排序建筑，通常有利于蓝图尺寸
+///
+/// 先按建筑类型分组，组内沿着传送带等同类建筑的连接排列，见[`topological_sort_buildings`]
 #[must_use]
 pub fn sort_buildings(buildings: Vec<Building>, reserved: bool) -> Vec<Building> {
-    let mut sorted = stable_sort_by_building_key(buildings);
+    let mut sorted = topological_sort_buildings(buildings);
 
     // 游戏内的建造顺序与蓝图顺序相反
     if reserved {
@@ -18,17 +56,30 @@ pub fn sort_buildings(buildings: Vec<Building>, reserved: bool) -> Vec<Building>
 // fn split_belt_and_non_belt(buildings: Vec<Building>) -> (Vec<Building>, Vec<Building>) {
 //     buildings
 //         .into_iter()
-//         .partition(|building| (2001..=2009).contains(&building.item_id))
+//         .partition(|building| building.kind() == BuildingKind::Belt)
 // }
 
-fn stable_sort_by_building_key(mut buildings: Vec<Building>) -> Vec<Building> {
+type GroupKey = (i16, i16, i16, i8);
+
+const fn group_key(building: &Building) -> GroupKey {
+    (
+        building.item_id,
+        building.model_index,
+        building.recipe_id,
+        building.area_index,
+    )
+}
+
+#[must_use]
+pub fn stable_sort_by_building_key(mut buildings: Vec<Building>) -> Vec<Building> {
     buildings.sort_by_cached_key(|building| {
         // 预计算排序键，实现Schwartzian transform优化
+        let (item_id, model_index, recipe_id, area_index) = group_key(building);
         (
-            building.item_id,
-            building.model_index,
-            building.recipe_id,
-            building.area_index,
+            item_id,
+            model_index,
+            recipe_id,
+            area_index,
             calculate_offset_score(building).to_bits(),
         )
     });
@@ -36,6 +87,109 @@ fn stable_sort_by_building_key(mut buildings: Vec<Building>) -> Vec<Building> {
     buildings
 }
 
+/// 只按分区和坐标排序建筑，同一位置上的不同建筑保持原有顺序
+#[must_use]
+pub fn stable_sort_by_position(mut buildings: Vec<Building>) -> Vec<Building> {
+    buildings.sort_by(|a, b| {
+        a.area_index
+            .cmp(&b.area_index)
+            .then(a.local_offset_y.total_cmp(&b.local_offset_y))
+            .then(a.local_offset_x.total_cmp(&b.local_offset_x))
+            .then(a.local_offset_z.total_cmp(&b.local_offset_z))
+    });
+
+    buildings
+}
+
+/// 按拓扑关系排序建筑，只改变顺序，不修改任何数据
+///
+/// * 先按建筑类型分组，分组的顺序与[`stable_sort_by_building_key`]相同
+/// * 组内沿着同类建筑的连接（如一条传送带）从头到尾连续排列，
+///   相邻建筑的坐标和index之差几乎都相同，有利于压缩
+/// * 不与同类建筑相连的建筑（如分拣器）按其输出、输入对象在排序结果中的位置排列
+#[must_use]
+pub fn topological_sort_buildings(buildings: Vec<Building>) -> Vec<Building> {
+    let sorted = stable_sort_by_building_key(buildings);
+    let graph = ConnectionGraph::new(&sorted);
+
+    let key_of = |i: usize| sorted.get(i).map(group_key);
+    let id_of = |i: usize| {
+        sorted
+            .get(i)
+            .map_or(Building::INDEX_NULL, |building| building.index)
+    };
+    let same_group_neighbor =
+        |i: usize, neighbors: Vec<usize>, is_usable: &dyn Fn(usize) -> bool| {
+            neighbors
+                .into_iter()
+                .filter(|&j| key_of(j) == key_of(i) && is_usable(j))
+                .min()
+        };
+
+    // 沿同类建筑的连接把每个组拆成若干条链
+    let mut visited = HashSet::new();
+    let mut chains = Vec::new();
+    for start in 0..sorted.len() {
+        if visited.contains(&start) {
+            continue;
+        }
+
+        let mut seen = HashSet::from([start]);
+        let mut head = start;
+        while let Some(previous) = same_group_neighbor(head, graph.upstream(id_of(head)), &|j| {
+            !visited.contains(&j) && !seen.contains(&j)
+        }) {
+            seen.insert(previous);
+            head = previous;
+        }
+
+        let mut chain = Vec::new();
+        let mut current = Some(head);
+        while let Some(i) = current {
+            visited.insert(i);
+            chain.push(i);
+            current =
+                same_group_neighbor(i, graph.downstream(id_of(i)), &|j| !visited.contains(&j));
+        }
+        chains.push(chain);
+    }
+
+    let rank = chains
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(rank, &i)| (i, rank))
+        .collect::<HashMap<_, _>>();
+    let rank_of = |i: usize| rank.get(&i).copied().unwrap_or(usize::MAX);
+    let linked_rank = |neighbors: Vec<usize>| neighbors.into_iter().map(rank_of).min();
+
+    let mut order = chains
+        .into_iter()
+        .flat_map(|chain| {
+            let is_single = chain.len() == 1;
+            chain.into_iter().map(move |i| (i, is_single))
+        })
+        .map(|(i, is_single)| {
+            let links = if is_single {
+                (
+                    linked_rank(graph.downstream(id_of(i))),
+                    linked_rank(graph.upstream(id_of(i))),
+                )
+            } else {
+                (None, None)
+            };
+            (key_of(i), links, rank_of(i), i)
+        })
+        .collect::<Vec<_>>();
+    order.sort_unstable();
+
+    let mut buildings = sorted.into_iter().map(Some).collect::<Vec<_>>();
+    order
+        .into_iter()
+        .filter_map(|(_, _, _, i)| buildings.get_mut(i).and_then(Option::take))
+        .collect()
+}
+
 fn calculate_offset_score(b: &Building) -> f64 {
     let (x, y, z) = (
         f64::from(b.local_offset_x),
@@ -44,3 +198,118 @@ fn calculate_offset_score(b: &Building) -> f64 {
     );
     y.mul_add(256.0, x).mul_add(1024.0, z)
 }
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blueprint::{
+        Content, Version, codec::content::string_from_data, editor::fix_index::fix_buildings_index,
+    };
+    use std::num::NonZero;
+
+    /// `lines`条传送带，第`k`条先向北走`2k + 1`格，再向东走`turn`格，最后向北走`turn`格，
+    /// 按坐标排序时不同传送带的节点会交错在一起。
+    /// 与真实蓝图一样，坐标不在整数网格上，每条传送带的小数部分各不相同
+    fn belt_lines(lines: i32, turn: i32) -> Vec<Building> {
+        let belts = (0..lines)
+            .flat_map(|k| {
+                let north = (0..=2 * k).map(move |y| (3 * k, y));
+                let east = (1..=turn).map(move |x| (3 * k + x, 2 * k));
+                let north_again = (1..=turn).map(move |y| (3 * k + turn, 2 * k + y));
+                let path = north.chain(east).chain(north_again).collect::<Vec<_>>();
+                let length = path.len();
+                path.into_iter()
+                    .enumerate()
+                    .map(move |(i, position)| (k, i, i + 1 == length, position))
+            })
+            .collect::<Vec<_>>();
+
+        belts
+            .iter()
+            .zip(0..)
+            .map(|(&(k, _, is_last, (x, y)), index)| {
+                #[expect(clippy::cast_precision_loss)]
+                let (local_offset_x, local_offset_y) = (
+                    (k as f32).mul_add(0.0123, x as f32 * 1.0037),
+                    (k as f32).mul_add(0.0456, y as f32 * 1.0011),
+                );
+                Building {
+                    index,
+                    item_id: 2001,
+                    model_index: 35,
+                    local_offset_x,
+                    local_offset_y,
+                    local_offset_x2: local_offset_x,
+                    local_offset_y2: local_offset_y,
+                    temp_output_obj_idx: if is_last {
+                        Building::INDEX_NULL
+                    } else {
+                        index + 1
+                    },
+                    output_to_slot: 1,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    fn compressed_size(buildings: Vec<Building>) -> usize {
+        let (buildings, warns) = fix_buildings_index(buildings);
+        assert!(warns.is_empty());
+        let content = Content {
+            buildings_length: u32::try_from(buildings.len()).unwrap(),
+            buildings,
+            ..Default::default()
+        };
+        let zopfli_options = zopfli::Options {
+            iteration_count: NonZero::new(1).unwrap(),
+            ..Default::default()
+        };
+        string_from_data(&content, &zopfli_options, &Version::Neg101, false)
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn test_topological_sort_follows_belts() {
+        let buildings = belt_lines(3, 2);
+        let sorted = topological_sort_buildings(stable_sort_by_building_key(buildings.clone()));
+        let positions = |buildings: &[Building]| {
+            buildings
+                .iter()
+                .map(|building| (building.local_offset_x, building.local_offset_y))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(positions(&sorted), positions(&buildings));
+    }
+
+    #[test]
+    fn test_topological_sort_is_smaller() {
+        let buildings = belt_lines(12, 10);
+        let by_key = compressed_size(stable_sort_by_building_key(buildings.clone()));
+        let topological = compressed_size(topological_sort_buildings(buildings));
+        assert!(topological < by_key, "{topological} >= {by_key}");
+    }
+
+    #[test]
+    fn test_sort_buildings_by_keeps_buildings() {
+        use strum::IntoEnumIterator;
+
+        let buildings = belt_lines(3, 2);
+        let mut expected = buildings.iter().map(|b| b.index).collect::<Vec<_>>();
+        expected.sort_unstable();
+        for strategy in SortStrategy::iter() {
+            let mut indexes = sort_buildings_by(buildings.clone(), strategy)
+                .iter()
+                .map(|b| b.index)
+                .collect::<Vec<_>>();
+            indexes.sort_unstable();
+            assert_eq!(indexes, expected, "{strategy:?}");
+        }
+        assert_eq!(
+            sort_buildings_by(buildings.clone(), SortStrategy::Original),
+            buildings
+        );
+    }
+}
@@ -1,7 +1,10 @@
+use crate::{
+    error::DspbptkError,
+    item::BuildingKind,
+    parameters::{ParameterView, read_parameters, write_parameters},
+};
 use nalgebra::Vector3;
 
-// TODO 把建筑类型改成枚举
-
 #[derive(Debug, Clone)]
 pub struct Building {
     pub uuid: Option<u128>,
@@ -29,6 +32,35 @@ pub struct Building {
     pub parameters: Vec<i32>,
 }
 
+impl Building {
+    /// 建筑类型，见[`BuildingKind::from_item_id`]
+    #[must_use]
+    pub fn kind(&self) -> BuildingKind {
+        BuildingKind::from_item_id(self.item_id)
+    }
+
+    /// 以类型化视图读取参数
+    ///
+    /// # Errors
+    /// 可能的原因：
+    /// * 建筑类型与视图不符
+    pub fn parameters_view<T: ParameterView>(&self) -> Result<T, DspbptkError> {
+        read_parameters(self.item_id, &self.parameters)
+    }
+
+    /// 把类型化视图写回参数
+    ///
+    /// # Errors
+    /// 可能的原因：
+    /// * 建筑类型与视图不符
+    pub fn with_parameters_view<T: ParameterView>(self, view: &T) -> Result<Self, DspbptkError> {
+        Ok(Self {
+            parameters: write_parameters(self.item_id, view, self.parameters)?,
+            ..self
+        })
+    }
+}
+
 impl Default for Building {
     fn default() -> Self {
         Self {
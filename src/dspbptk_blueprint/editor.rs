@@ -1,3 +1,7 @@
 pub mod belt;
 pub mod fix_uuid;
+pub mod mirror;
 pub mod offset;
+pub mod relocate;
+pub mod rotate;
+pub mod station;
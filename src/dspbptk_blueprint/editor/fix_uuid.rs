@@ -1,27 +1,86 @@
-use crate::dspbptk_blueprint::Building;
+use crate::{dspbptk_blueprint::Building, error::DspbptkWarn, graph::Reference};
 use std::collections::HashMap;
 
+/// 把uuid重排为建筑在数组中的下标
+///
+/// 指向不存在的建筑的`temp_output_obj_idx`和`temp_input_obj_idx`会被置为`None`，
+/// 每个被丢弃的引用以警告的形式返回，其中的uuid是重排前的uuid，便于在输入中找到对应的建筑
 #[must_use]
-pub fn fix_dspbptk_buildings_index(buildings: Vec<Building>) -> Vec<Building> {
+pub fn fix_dspbptk_buildings_index(buildings: Vec<Building>) -> (Vec<Building>, Vec<DspbptkWarn>) {
     let uuid_lut = buildings
         .iter()
         .enumerate()
         .map(|(uuid, building)| (building.uuid, Some(uuid as u128)))
         .collect::<HashMap<_, _>>();
 
-    buildings
+    let mut warns = Vec::new();
+    let buildings = buildings
         .into_iter()
-        .map(|building| Building {
-            uuid: *uuid_lut.get(&building.uuid).unwrap_or(&None),
-            temp_output_obj_idx: uuid_lut
-                .get(&building.temp_output_obj_idx)
-                .copied()
-                .unwrap_or(None),
-            temp_input_obj_idx: uuid_lut
-                .get(&building.temp_input_obj_idx)
-                .copied()
-                .unwrap_or(None),
-            ..building
+        .map(|building| {
+            let mut dropped = |uuid: Option<u128>, link: Reference| {
+                uuid.and_then(|uuid| {
+                    let fixed = uuid_lut.get(&Some(uuid)).copied().flatten();
+                    if fixed.is_none() {
+                        warns.push(DspbptkWarn::DroppedUuidReference {
+                            uuid: building.uuid,
+                            item_id: building.item_id,
+                            link,
+                        });
+                    }
+                    fixed
+                })
+            };
+            let temp_output_obj_idx = dropped(building.temp_output_obj_idx, Reference::Output);
+            let temp_input_obj_idx = dropped(building.temp_input_obj_idx, Reference::Input);
+            Building {
+                uuid: *uuid_lut.get(&building.uuid).unwrap_or(&None),
+                temp_output_obj_idx,
+                temp_input_obj_idx,
+                ..building
+            }
         })
-        .collect()
+        .collect();
+
+    (buildings, warns)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fix_dspbptk_buildings_index() {
+        let buildings = vec![
+            Building {
+                uuid: Some(10),
+                temp_output_obj_idx: Some(20),
+                ..Default::default()
+            },
+            Building {
+                uuid: Some(20),
+                item_id: 2001,
+                temp_input_obj_idx: Some(10),
+                temp_output_obj_idx: Some(30),
+                ..Default::default()
+            },
+        ];
+
+        let (buildings, warns) = fix_dspbptk_buildings_index(buildings);
+
+        assert_eq!(
+            buildings
+                .iter()
+                .map(|b| (b.uuid, b.temp_output_obj_idx, b.temp_input_obj_idx))
+                .collect::<Vec<_>>(),
+            [(Some(0), Some(1), None), (Some(1), None, Some(0))]
+        );
+        assert_eq!(
+            warns,
+            [DspbptkWarn::DroppedUuidReference {
+                uuid: Some(20),
+                item_id: 2001,
+                link: Reference::Output,
+            }]
+        );
+    }
 }
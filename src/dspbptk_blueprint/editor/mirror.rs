@@ -0,0 +1,147 @@
+use crate::{
+    dspbptk_blueprint::Building,
+    item::{BuildingKind, Item, ModelIndex},
+};
+use clap::ValueEnum;
+use nalgebra::Vector3;
+use std::collections::HashMap;
+
+/// 镜像所用的对称轴
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// 平行于x轴的直线`y = coordinate`，即上下翻转
+    X,
+    /// 平行于y轴的直线`x = coordinate`，即左右翻转
+    Y,
+}
+
+/// 镜像后建筑上的接口编号，只处理已知布局的建筑，其他建筑的接口编号保持不变
+///
+/// * 传送带：0前1后，2和3是左右两侧，镜像后交换
+/// * 四向分流器：0~3环绕一周，以0和2所在的直线为轴镜像，交换1和3
+fn mirror_slot(item_id: Option<i16>, slot: i8) -> i8 {
+    match (item_id.map(BuildingKind::from_item_id), slot) {
+        (Some(BuildingKind::Belt), 2) | (Some(BuildingKind::Splitter), 1) => 3,
+        (Some(BuildingKind::Belt), 3) => 2,
+        (Some(BuildingKind::Splitter), 3) => 1,
+        _ => slot,
+    }
+}
+
+/// 模型为`ModelIndex::Triple`的建筑（四向分流器），后两个模型互为镜像，镜像后交换
+fn mirror_model_index(item_id: i16, model_index: i16) -> i16 {
+    match Item::try_from(item_id).map(|item| item.model()) {
+        Ok(ModelIndex::Triple(_, left, right)) if model_index == left => right,
+        Ok(ModelIndex::Triple(_, left, right)) if model_index == right => left,
+        _ => model_index,
+    }
+}
+
+impl Building {
+    /// 以`axis`和`coordinate`确定的直线为轴镜像
+    ///
+    /// `output_obj_item_id`和`input_obj_item_id`是输出、输入对象的`item_id`，用于修正指向对象的接口编号
+    #[must_use]
+    pub fn mirror(
+        self,
+        axis: Axis,
+        coordinate: f64,
+        output_obj_item_id: Option<i16>,
+        input_obj_item_id: Option<i16>,
+    ) -> Self {
+        // yaw与罗盘方位角一致，0°指向+y，90°指向+x
+        let reflect = |v: Vector3<f64>| match axis {
+            Axis::X => Vector3::new(v.x, 2.0_f64.mul_add(coordinate, -v.y), v.z),
+            Axis::Y => Vector3::new(2.0_f64.mul_add(coordinate, -v.x), v.y, v.z),
+        };
+        let reflect_yaw = |yaw: f64| match axis {
+            Axis::X => (180.0 - yaw).rem_euclid(360.0),
+            Axis::Y => (360.0 - yaw).rem_euclid(360.0),
+        };
+
+        Self {
+            local_offset: reflect(self.local_offset),
+            yaw: reflect_yaw(self.yaw),
+            local_offset_2: reflect(self.local_offset_2),
+            yaw2: reflect_yaw(self.yaw2),
+            model_index: mirror_model_index(self.item_id, self.model_index),
+            output_to_slot: mirror_slot(output_obj_item_id, self.output_to_slot),
+            input_from_slot: mirror_slot(input_obj_item_id, self.input_from_slot),
+            output_from_slot: mirror_slot(Some(self.item_id), self.output_from_slot),
+            input_to_slot: mirror_slot(Some(self.item_id), self.input_to_slot),
+            ..self
+        }
+    }
+}
+
+/// 以`axis`和`coordinate`确定的直线为轴镜像整个模块，同时修正朝向、手性模型和接口编号
+#[must_use]
+pub fn mirror(module: Vec<Building>, axis: Axis, coordinate: f64) -> Vec<Building> {
+    let item_ids = module
+        .iter()
+        .filter_map(|building| building.uuid.map(|uuid| (uuid, building.item_id)))
+        .collect::<HashMap<_, _>>();
+    let item_id_of = |uuid: Option<u128>| uuid.and_then(|uuid| item_ids.get(&uuid).copied());
+
+    module
+        .into_iter()
+        .map(|building| {
+            let output_obj_item_id = item_id_of(building.temp_output_obj_idx);
+            let input_obj_item_id = item_id_of(building.temp_input_obj_idx);
+            building.mirror(axis, coordinate, output_obj_item_id, input_obj_item_id)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mirror() {
+        let splitter = Building {
+            uuid: Some(0),
+            item_id: Item::四向分流器 as i16,
+            model_index: 39,
+            local_offset: Vector3::new(1.0, 2.0, 0.0),
+            yaw: 90.0,
+            ..Default::default()
+        };
+        let belt = Building {
+            uuid: Some(1),
+            item_id: Item::传送带 as i16,
+            local_offset: Vector3::new(2.0, 3.0, 1.0),
+            yaw: 30.0,
+            temp_output_obj_idx: Some(0),
+            output_to_slot: 1,
+            input_to_slot: 2,
+            ..Default::default()
+        };
+
+        let mirrored = mirror(vec![splitter, belt], Axis::Y, 1.0);
+        let [splitter, belt] = mirrored.as_slice() else {
+            panic!("unexpected buildings count");
+        };
+
+        assert_eq!(splitter.local_offset, Vector3::new(1.0, 2.0, 0.0));
+        assert!((splitter.yaw - 270.0).abs() < f64::EPSILON);
+        assert_eq!(splitter.model_index, 40);
+
+        assert_eq!(belt.local_offset, Vector3::new(0.0, 3.0, 1.0));
+        assert!((belt.yaw - 330.0).abs() < f64::EPSILON);
+        assert_eq!(belt.output_to_slot, 3);
+        assert_eq!(belt.input_to_slot, 3);
+    }
+
+    #[test]
+    fn test_mirror_x() {
+        let belt = Building {
+            local_offset: Vector3::new(2.0, 3.0, 1.0),
+            yaw: 30.0,
+            ..Default::default()
+        };
+        let mirrored = belt.mirror(Axis::X, 1.0, None, None);
+        assert_eq!(mirrored.local_offset, Vector3::new(2.0, -1.0, 1.0));
+        assert!((mirrored.yaw - 150.0).abs() < f64::EPSILON);
+    }
+}
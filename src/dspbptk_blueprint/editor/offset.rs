@@ -1,5 +1,9 @@
-use crate::dspbptk_blueprint::{Building, uuid::new_uuid};
-use nalgebra::Vector3;
+use crate::{
+    dspbptk_blueprint::{Building, editor::relocate::relocation, uuid::UuidAllocator},
+    planet::unit_conversion::{EQUATORIAL_GRID, HALF_EQUATORIAL_GRID, arc_from_grid},
+};
+use clap::ValueEnum;
+use nalgebra::{Vector2, Vector3};
 
 impl Building {
     #[must_use]
@@ -19,9 +23,18 @@ impl Building {
     }
 }
 
+/// 模块中所有建筑的uuid，用于为副本分配不重复的uuid，见[`UuidAllocator::new_offset`]
+fn module_uuids(module: &[Building]) -> Vec<u128> {
+    module.iter().filter_map(|building| building.uuid).collect()
+}
+
 #[must_use]
-pub fn offset(module: Vec<Building>, basis_vector: &Vector3<f64>) -> Vec<Building> {
-    let index_offset = new_uuid();
+pub fn offset(
+    module: Vec<Building>,
+    basis_vector: &Vector3<f64>,
+    uuids: &mut UuidAllocator,
+) -> Vec<Building> {
+    let index_offset = uuids.new_offset(&module_uuids(&module));
     module
         .into_iter()
         .map(move |building| building.offset(basis_vector, index_offset))
@@ -34,6 +47,7 @@ pub fn offset(module: Vec<Building>, basis_vector: &Vector3<f64>) -> Vec<Buildin
 /// * `module` - 基础建筑模块的数据数组
 /// * `basis_vector` - 线性排列的基向量，决定排列方向和单步长度
 /// * `count` - 需要生成的实例数量
+/// * `uuids` - 为每个实例分配不重复的uuid
 ///
 /// # 返回值
 /// 包含所有偏移后建筑模块的向量，每个模块按线性模式排列。
@@ -42,15 +56,297 @@ pub fn linear_pattern(
     module: &[Building],
     basis_vector: &Vector3<f64>,
     count: u32,
+    uuids: &mut UuidAllocator,
 ) -> Vec<Building> {
-    let index_offset_0 = new_uuid();
+    let index_offsets = new_offsets(module, count, uuids);
     (0..count)
-        .flat_map(|i| {
+        .zip(index_offsets)
+        .flat_map(|(i, index_offset)| {
             let offset = f64::from(i) * basis_vector;
-            let index_offset = index_offset_0 + u128::from(i);
             module
                 .iter()
                 .map(move |building| building.clone().offset(&offset, index_offset))
         })
         .collect()
 }
+
+/// 为`count`个实例依次分配uuid的偏移量
+fn new_offsets(module: &[Building], count: u32, uuids: &mut UuidAllocator) -> Vec<u128> {
+    let module_uuids = module_uuids(module);
+    (0..count)
+        .map(|_| uuids.new_offset(&module_uuids))
+        .collect()
+}
+
+/// 把经度（格）规范到[-500, 500)
+fn wrap_longitude(local_offset: &Vector3<f64>) -> Vector3<f64> {
+    Vector3::new(
+        (local_offset.x + HALF_EQUATORIAL_GRID).rem_euclid(EQUATORIAL_GRID) - HALF_EQUATORIAL_GRID,
+        local_offset.y,
+        local_offset.z,
+    )
+}
+
+/// 生成绕行星自转轴的环形阵列
+///
+/// # 参数
+/// * `module` - 基础建筑模块的数据数组
+/// * `count` - 沿赤道均匀分布的实例数量
+/// * `latitude_shift` - 每个实例相对于上一个实例的纬度偏移（格），为0时各实例只在经度上平移
+/// * `origin` - 模块所在的经度、纬度（格），纬度偏移时沿经过此处的经线移动，见[`relocation`]
+/// * `uuids` - 为每个实例分配不重复的uuid
+///
+/// # 返回值
+/// 包含所有实例的向量，每个实例都有新的uuid
+#[must_use]
+pub fn circular_pattern(
+    module: &[Building],
+    count: u32,
+    latitude_shift: f64,
+    origin: &Vector2<f64>,
+    uuids: &mut UuidAllocator,
+) -> Vec<Building> {
+    let index_offsets = new_offsets(module, count, uuids);
+    let longitude_step = EQUATORIAL_GRID / f64::from(count);
+    (0..count)
+        .zip(index_offsets)
+        .flat_map(|(i, index_offset)| {
+            let longitude_offset = f64::from(i) * longitude_step;
+            let latitude_offset = f64::from(i) * latitude_shift;
+            let rotation = relocation(
+                origin,
+                &Vector2::new(origin.x + longitude_offset, origin.y + latitude_offset),
+            );
+            module.iter().map(move |building| {
+                if latitude_offset == 0.0 {
+                    // 只在经度上平移时直接修改网格坐标，避免引入浮点误差
+                    let building = building
+                        .clone()
+                        .offset(&Vector3::new(longitude_offset, 0.0, 0.0), index_offset);
+                    Building {
+                        local_offset: wrap_longitude(&building.local_offset),
+                        local_offset_2: wrap_longitude(&building.local_offset_2),
+                        ..building
+                    }
+                } else {
+                    building
+                        .clone()
+                        .offset(&Vector3::zeros(), index_offset)
+                        .relocate(&rotation)
+                }
+            })
+        })
+        .collect()
+}
+
+/// 网格阵列中，远离赤道的行如何补偿纬线长度的缩短
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LatitudeCompensation {
+    /// 不补偿，直接在网格上平移，等价于两次`linear_pattern`
+    #[default]
+    None,
+    /// 每行的数量不变，按纬度拉伸经度方向的间距
+    Stretch,
+    /// 每行的间距按纬度拉伸，并减少数量使这一行的总经度跨度不超过第一行
+    Reduce,
+}
+
+/// 第`row`行模块最靠近极点一侧的纬度（弧度），`y_range`是模块建筑的纬度范围（格）
+fn poleward_edge(y_range: (f64, f64), step_y: f64, row: u32) -> f64 {
+    let (y_min, y_max) = y_range;
+    let offset = f64::from(row) * step_y;
+    arc_from_grid((y_min + offset).abs().max((y_max + offset).abs()))
+}
+
+/// 生成二维网格阵列的建筑模块实例
+///
+/// # 参数
+/// * `module` - 基础建筑模块的数据数组
+/// * `basis_vector` - 经度、纬度方向的单步长度（格），以第一行为准
+/// * `count_x` - 每行的实例数量，补偿方式为`Reduce`时是最大数量
+/// * `count_y` - 行数
+/// * `origin` - 模块所在的经度、纬度（格），补偿时各实例沿球面从这里移动到目标位置，见[`relocation`]
+/// * `compensation` - 纬度补偿方式
+/// * `uuids` - 为每个实例分配不重复的uuid
+///
+/// 补偿时每行的拉伸倍率为第一行与这一行最靠近极点一侧纬度的余弦之比，与`Draft::push`计算每行数量的方式相同，
+/// 因此实例靠近极点时也不会互相重叠；到达极点的行会被丢弃
+///
+/// # 返回值
+/// 包含所有实例的向量，每个实例都有新的uuid
+#[must_use]
+pub fn grid_pattern(
+    module: &[Building],
+    basis_vector: &Vector2<f64>,
+    count_x: u32,
+    count_y: u32,
+    origin: &Vector2<f64>,
+    compensation: LatitudeCompensation,
+    uuids: &mut UuidAllocator,
+) -> Vec<Building> {
+    let y_range = module
+        .iter()
+        .map(|building| building.local_offset.y)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(y_min, y_max), y| {
+            (y_min.min(y), y_max.max(y))
+        });
+    let edge_cos_0 = poleward_edge(y_range, basis_vector.y, 0).cos();
+    let module_uuids = module_uuids(module);
+
+    (0..count_y)
+        .flat_map(|j| {
+            let stretch = poleward_edge(y_range, basis_vector.y, j).cos() / edge_cos_0;
+            let (step_x, count) = match compensation {
+                LatitudeCompensation::None => (basis_vector.x, count_x),
+                _ if stretch <= 0.0 || !stretch.is_finite() => (basis_vector.x, 0),
+                LatitudeCompensation::Stretch => (basis_vector.x / stretch, count_x),
+                LatitudeCompensation::Reduce => {
+                    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let count = (f64::from(count_x) * stretch)
+                        .floor()
+                        .min(f64::from(count_x)) as u32;
+                    (basis_vector.x / stretch, count)
+                }
+            };
+            (0..count).map(move |i| (i, j, step_x))
+        })
+        .map(|(i, j, step_x)| (i, j, step_x, uuids.new_offset(&module_uuids)))
+        .flat_map(|(i, j, step_x, index_offset)| {
+            let offset = Vector2::new(f64::from(i) * step_x, f64::from(j) * basis_vector.y);
+            let rotation = relocation(origin, &(origin + offset));
+            module.iter().map(move |building| {
+                let building = building.clone();
+                if compensation == LatitudeCompensation::None || offset.y == 0.0 {
+                    // 同一行内只在经度上平移，直接修改网格坐标，避免引入浮点误差
+                    building.offset(&Vector3::new(offset.x, offset.y, 0.0), index_offset)
+                } else {
+                    building
+                        .offset(&Vector3::zeros(), index_offset)
+                        .relocate(&rotation)
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_circular_pattern() {
+        let module = [Building {
+            uuid: Some(0),
+            local_offset: Vector3::new(400.0, 10.0, 0.0),
+            local_offset_2: Vector3::new(400.0, 10.0, 0.0),
+            ..Default::default()
+        }];
+
+        let pattern = circular_pattern(
+            &module,
+            4,
+            0.0,
+            &Vector2::new(400.0, 10.0),
+            &mut UuidAllocator::from_seed(0),
+        );
+        let longitudes = pattern
+            .iter()
+            .map(|building| building.local_offset.x)
+            .collect::<Vec<_>>();
+        assert_eq!(longitudes, [400.0, -350.0, -100.0, 150.0]);
+        assert_eq!(
+            pattern
+                .iter()
+                .map(|building| building.uuid)
+                .collect::<HashSet<_>>()
+                .len(),
+            4
+        );
+        // 种子相同时uuid也相同
+        let uuids_of = |pattern: &[Building]| {
+            pattern
+                .iter()
+                .map(|building| building.uuid)
+                .collect::<Vec<_>>()
+        };
+        let again = circular_pattern(
+            &module,
+            4,
+            0.0,
+            &Vector2::new(400.0, 10.0),
+            &mut UuidAllocator::from_seed(0),
+        );
+        assert_eq!(uuids_of(&again), uuids_of(&pattern));
+
+        let pattern = circular_pattern(
+            &module,
+            2,
+            20.0,
+            &Vector2::new(400.0, 10.0),
+            &mut UuidAllocator::from_seed(0),
+        );
+        let [first, second] = pattern.as_slice() else {
+            panic!("unexpected buildings count");
+        };
+        assert!((first.local_offset.y - 10.0).abs() < f64::EPSILON);
+        assert!((second.local_offset.x - -100.0).abs() < 1e-9);
+        assert!((second.local_offset.y - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_grid_pattern() {
+        let module = [Building {
+            uuid: Some(0),
+            ..Default::default()
+        }];
+        let basis_vector = Vector2::new(10.0, 100.0);
+        let origin = Vector2::new(0.0, 0.0);
+        let count_in_row = |pattern: &[Building], y: f64| {
+            pattern
+                .iter()
+                .filter(|building| (building.local_offset.y - y).abs() < 1e-9)
+                .count()
+        };
+
+        let pattern = grid_pattern(
+            &module,
+            &basis_vector,
+            3,
+            3,
+            &origin,
+            LatitudeCompensation::None,
+            &mut UuidAllocator::from_seed(0),
+        );
+        assert_eq!(pattern.len(), 9);
+
+        // cos(36°) = 0.809，cos(72°) = 0.309
+        let pattern = grid_pattern(
+            &module,
+            &basis_vector,
+            3,
+            3,
+            &origin,
+            LatitudeCompensation::Reduce,
+            &mut UuidAllocator::from_seed(0),
+        );
+        assert_eq!(count_in_row(&pattern, 0.0), 3);
+        assert_eq!(count_in_row(&pattern, 100.0), 2);
+        assert_eq!(count_in_row(&pattern, 200.0), 0);
+
+        let pattern = grid_pattern(
+            &module,
+            &basis_vector,
+            3,
+            2,
+            &origin,
+            LatitudeCompensation::Stretch,
+            &mut UuidAllocator::from_seed(0),
+        );
+        let stretched = pattern
+            .iter()
+            .map(|building| building.local_offset.x)
+            .fold(0.0, f64::max);
+        assert!((stretched - 20.0 / arc_from_grid(100.0).cos()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    dspbptk_blueprint::Building,
+    planet::unit_conversion::{
+        arc_from_grid, direction_to_local_offset, local_offset_to_direction, tangent_to_yaw,
+        yaw_to_tangent,
+    },
+};
+use nalgebra::{Rotation3, Vector2, Vector3};
+
+/// 把球面上的点`from`沿球面移动到`to`的旋转，`from`与`to`是以格为单位的经度、纬度
+///
+/// 先把`from`转到经度0°，再沿经线移动到目标纬度，最后转到目标经度，
+/// 因此经度0°上南北方向的移动不会改变建筑的朝向
+#[must_use]
+pub fn relocation(from: &Vector2<f64>, to: &Vector2<f64>) -> Rotation3<f64> {
+    Rotation3::from_axis_angle(&Vector3::z_axis(), arc_from_grid(to.x))
+        * Rotation3::from_axis_angle(&Vector3::y_axis(), -arc_from_grid(to.y - from.y))
+        * Rotation3::from_axis_angle(&Vector3::z_axis(), -arc_from_grid(from.x))
+}
+
+/// 旋转`local_offset`和`yaw`，高度不变
+fn relocate_pose(
+    local_offset: &Vector3<f64>,
+    yaw: f64,
+    rotation: &Rotation3<f64>,
+) -> (Vector3<f64>, f64) {
+    let direction = local_offset_to_direction(*local_offset);
+    let tangent = yaw_to_tangent(&direction, yaw);
+
+    let direction_out = rotation * direction;
+    let tangent_out = rotation * tangent;
+
+    (
+        direction_to_local_offset(&direction_out, local_offset.z),
+        tangent_to_yaw(&direction_out, &tangent_out),
+    )
+}
+
+impl Building {
+    /// 按球面上的旋转移动建筑，并修正`yaw`和`yaw2`，见[`relocation`]
+    #[must_use]
+    pub fn relocate(self, rotation: &Rotation3<f64>) -> Self {
+        let (local_offset, yaw) = relocate_pose(&self.local_offset, self.yaw, rotation);
+        let (local_offset_2, yaw2) = relocate_pose(&self.local_offset_2, self.yaw2, rotation);
+        Self {
+            local_offset,
+            yaw,
+            local_offset_2,
+            yaw2,
+            ..self
+        }
+    }
+}
+
+/// 把模块沿球面从`from`移动到`to`，建筑之间的实际距离和相对朝向保持不变
+///
+/// 与`offset`直接在网格上平移不同，移向两极时东西方向的网格间距会随纬度拉伸
+#[must_use]
+pub fn relocate(module: Vec<Building>, from: &Vector2<f64>, to: &Vector2<f64>) -> Vec<Building> {
+    let rotation = relocation(from, to);
+    module
+        .into_iter()
+        .map(|building| building.relocate(&rotation))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn test_relocate() {
+        let west = Building {
+            local_offset: Vector3::new(-0.5, 0.0, 1.0),
+            ..Default::default()
+        };
+        let center = Building {
+            yaw: 30.0,
+            ..Default::default()
+        };
+        let east = Building {
+            local_offset: Vector3::new(0.5, 0.0, 1.0),
+            ..Default::default()
+        };
+        let latitude = 100.0;
+
+        let relocated = relocate(
+            vec![west, center, east],
+            &Vector2::new(0.0, 0.0),
+            &Vector2::new(0.0, latitude),
+        );
+        let [west, center, east] = relocated.as_slice() else {
+            panic!("unexpected buildings count");
+        };
+
+        // 网格上的间距被拉伸，实际距离不变
+        let arc = local_offset_to_direction(east.local_offset)
+            .angle(&local_offset_to_direction(west.local_offset));
+        assert!(east.local_offset.x - west.local_offset.x > 1.0);
+        assert!((arc - arc_from_grid(1.0)).abs() < EPSILON);
+        assert!((west.local_offset.x + east.local_offset.x).abs() < EPSILON);
+        assert!((west.local_offset.z - 1.0).abs() < EPSILON);
+
+        assert!((center.local_offset.y - latitude).abs() < EPSILON);
+        assert!((center.yaw - 30.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_relocate_longitude() {
+        let building = Building {
+            local_offset: Vector3::new(10.0, 20.0, 0.0),
+            yaw: 45.0,
+            ..Default::default()
+        };
+        let relocated = building.relocate(&relocation(
+            &Vector2::new(0.0, 20.0),
+            &Vector2::new(300.0, 20.0),
+        ));
+        assert!((relocated.local_offset.x - 310.0).abs() < EPSILON);
+        assert!((relocated.local_offset.y - 20.0).abs() < EPSILON);
+        assert!((relocated.yaw - 45.0).abs() < EPSILON);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::dspbptk_blueprint::Building;
+use nalgebra::Vector3;
+
+/// 角度为90°的整数倍时返回精确值，避免浮点误差破坏建筑对齐
+fn sin_cos_degrees(angle: f64) -> (f64, f64) {
+    let angle = angle.rem_euclid(360.0);
+    match angle {
+        0.0 => (0.0, 1.0),
+        90.0 => (1.0, 0.0),
+        180.0 => (0.0, -1.0),
+        270.0 => (-1.0, 0.0),
+        _ => angle.to_radians().sin_cos(),
+    }
+}
+
+/// 把`local_offset`绕过`pivot`的竖直轴顺时针（俯视）旋转
+fn rotate_local_offset(
+    local_offset: &Vector3<f64>,
+    pivot: &Vector3<f64>,
+    (sin, cos): (f64, f64),
+) -> Vector3<f64> {
+    let dx = local_offset.x - pivot.x;
+    let dy = local_offset.y - pivot.y;
+    Vector3::new(
+        dx.mul_add(cos, dy * sin) + pivot.x,
+        (-dx).mul_add(sin, dy * cos) + pivot.y,
+        local_offset.z,
+    )
+}
+
+impl Building {
+    /// 绕过`pivot`的竖直轴顺时针（俯视）旋转`angle`度
+    ///
+    /// `yaw`与罗盘方位角一致，0°指向+y，90°指向+x，因此顺时针旋转时`yaw`直接加上`angle`
+    #[must_use]
+    pub fn rotate(self, pivot: &Vector3<f64>, angle: f64) -> Self {
+        let sin_cos = sin_cos_degrees(angle);
+        Self {
+            local_offset: rotate_local_offset(&self.local_offset, pivot, sin_cos),
+            yaw: (self.yaw + angle).rem_euclid(360.0),
+            local_offset_2: rotate_local_offset(&self.local_offset_2, pivot, sin_cos),
+            yaw2: (self.yaw2 + angle).rem_euclid(360.0),
+            ..self
+        }
+    }
+}
+
+/// 把整个模块绕过`pivot`的竖直轴顺时针（俯视）旋转`angle`度，建筑之间的连接关系不变
+#[must_use]
+pub fn rotate(module: Vec<Building>, pivot: &Vector3<f64>, angle: f64) -> Vec<Building> {
+    module
+        .into_iter()
+        .map(|building| building.rotate(pivot, angle))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rotate() {
+        let building = Building {
+            local_offset: Vector3::new(1.0, 3.0, 2.0),
+            yaw: 270.0,
+            local_offset_2: Vector3::new(1.0, 4.0, 2.0),
+            yaw2: 0.0,
+            ..Default::default()
+        };
+        let pivot = Vector3::new(1.0, 1.0, 0.0);
+
+        let rotated = building.clone().rotate(&pivot, 90.0);
+        assert_eq!(rotated.local_offset, Vector3::new(3.0, 1.0, 2.0));
+        assert_eq!(rotated.local_offset_2, Vector3::new(4.0, 1.0, 2.0));
+        assert!((rotated.yaw - 0.0).abs() < f64::EPSILON);
+        assert!((rotated.yaw2 - 90.0).abs() < f64::EPSILON);
+
+        let restored = rotated.rotate(&pivot, -90.0);
+        assert_eq!(restored.local_offset, building.local_offset);
+        assert!((restored.yaw - building.yaw).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::{
+    dspbptk_blueprint::Building,
+    error::DspbptkError,
+    item::Item,
+    parameters::station::{
+        LogisticRole, METERS_PER_AU, METERS_PER_LIGHT_YEAR, StationParameters, StationStorage,
+        TRIP_RANGE_DRONES_SCALE,
+    },
+};
+
+/// 选择物品格的条件，未指定的条件总是满足
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageFilter {
+    /// 运输站的类型，只会匹配到行星内物流运输站和星际物流运输站
+    pub station: Option<Item>,
+    /// 物品格的编号，从1开始，与游戏内一致
+    pub index: Option<usize>,
+    pub item_id: Option<i32>,
+    pub local_role: Option<LogisticRole>,
+    pub remote_role: Option<LogisticRole>,
+}
+
+impl StorageFilter {
+    #[must_use]
+    pub fn matches(&self, station_item_id: i16, index: usize, storage: &StationStorage) -> bool {
+        self.station
+            .is_none_or(|station| i16::from(station) == station_item_id)
+            && self.index.is_none_or(|expected| expected == index)
+            && self
+                .item_id
+                .is_none_or(|item_id| item_id == storage.item_id)
+            && self
+                .local_role
+                .is_none_or(|role| role == storage.local_role)
+            && self
+                .remote_role
+                .is_none_or(|role| role == storage.remote_role)
+    }
+}
+
+/// 对物品格的修改，未指定的字段保持不变
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageEdit {
+    pub item_id: Option<i32>,
+    pub local_role: Option<LogisticRole>,
+    pub remote_role: Option<LogisticRole>,
+    pub max: Option<i32>,
+}
+
+impl StorageEdit {
+    #[must_use]
+    pub fn apply(&self, storage: StationStorage) -> StationStorage {
+        StationStorage {
+            item_id: self.item_id.unwrap_or(storage.item_id),
+            local_role: self.local_role.unwrap_or(storage.local_role),
+            remote_role: self.remote_role.unwrap_or(storage.remote_role),
+            max: self.max.unwrap_or(storage.max),
+        }
+    }
+}
+
+/// 对运输站设置的修改，未指定的字段保持不变，各字段的含义见[`StationParameters`]
+///
+/// 路程使用游戏界面中的单位，写入时换算为参数中储存的值
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StationSettingsEdit {
+    /// 运输机最远路程，单位为度
+    pub trip_range_drones: Option<f64>,
+    /// 运输船最远路程，单位为光年
+    pub trip_range_ships: Option<f64>,
+    pub include_orbit_collector: Option<bool>,
+    /// 曲速启用路程，单位为AU
+    pub warp_enable_distance: Option<f64>,
+    pub warper_necessary: Option<bool>,
+    pub delivery_drones: Option<i32>,
+    pub delivery_ships: Option<i32>,
+    pub piler_count: Option<i32>,
+}
+
+// 超出i32范围的值饱和到边界
+#[expect(clippy::cast_possible_truncation)]
+const fn round_to_parameter(value: f64) -> i32 {
+    value.round() as i32
+}
+
+impl StationSettingsEdit {
+    #[must_use]
+    pub fn apply(&self, station: StationParameters) -> StationParameters {
+        StationParameters {
+            trip_range_drones: self
+                .trip_range_drones
+                .map_or(station.trip_range_drones, |degrees| {
+                    round_to_parameter(degrees.to_radians().cos() * TRIP_RANGE_DRONES_SCALE)
+                }),
+            trip_range_ships: self
+                .trip_range_ships
+                .map_or(station.trip_range_ships, |light_years| {
+                    round_to_parameter(light_years * METERS_PER_LIGHT_YEAR)
+                }),
+            include_orbit_collector: self
+                .include_orbit_collector
+                .unwrap_or(station.include_orbit_collector),
+            warp_enable_distance: self
+                .warp_enable_distance
+                .map_or(station.warp_enable_distance, |au| {
+                    round_to_parameter(au * METERS_PER_AU)
+                }),
+            warper_necessary: self.warper_necessary.unwrap_or(station.warper_necessary),
+            delivery_drones: self.delivery_drones.unwrap_or(station.delivery_drones),
+            delivery_ships: self.delivery_ships.unwrap_or(station.delivery_ships),
+            piler_count: self.piler_count.unwrap_or(station.piler_count),
+            ..station
+        }
+    }
+}
+
+/// 查询到的一个物品格
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageMatch {
+    /// 运输站的uuid
+    pub uuid: Option<u128>,
+    /// 运输站的`item_id`
+    pub station_item_id: i16,
+    /// 物品格的编号，从1开始
+    pub index: usize,
+    pub storage: StationStorage,
+}
+
+// 轨道采集器也使用运输站的参数，但它的物品格和设置不能随意修改，所以不在查询和修改的范围内
+fn station_parameters(building: &Building) -> Option<StationParameters> {
+    matches!(
+        Item::try_from(building.item_id),
+        Ok(Item::行星内物流运输站 | Item::星际物流运输站)
+    )
+    .then(|| building.parameters_view().ok())
+    .flatten()
+}
+
+/// 查询所有运输站中满足条件的物品格
+#[must_use]
+pub fn query_storage(buildings: &[Building], filter: &StorageFilter) -> Vec<StorageMatch> {
+    buildings
+        .iter()
+        .filter_map(|building| station_parameters(building).map(|station| (building, station)))
+        .flat_map(|(building, station)| {
+            station
+                .storage
+                .into_iter()
+                .zip(1..)
+                .filter(|(storage, index)| filter.matches(building.item_id, *index, storage))
+                .map(|(storage, index)| StorageMatch {
+                    uuid: building.uuid,
+                    station_item_id: building.item_id,
+                    index,
+                    storage,
+                })
+        })
+        .collect()
+}
+
+/// 修改所有运输站中满足条件的物品格，有物品格满足条件的运输站同时修改运输站的设置
+///
+/// # Errors
+/// 可能的原因：
+/// * 运输站的参数无法写回，这个错误通常不该出现
+pub fn edit_storage(
+    buildings: Vec<Building>,
+    filter: &StorageFilter,
+    storage_edit: &StorageEdit,
+    settings_edit: &StationSettingsEdit,
+) -> Result<Vec<Building>, DspbptkError> {
+    buildings
+        .into_iter()
+        .map(|building| {
+            let Some(station) = station_parameters(&building) else {
+                return Ok(building);
+            };
+
+            let mut is_matched = false;
+            let storage = station
+                .storage
+                .iter()
+                .zip(1..)
+                .map(|(&storage, index)| {
+                    if filter.matches(building.item_id, index, &storage) {
+                        is_matched = true;
+                        storage_edit.apply(storage)
+                    } else {
+                        storage
+                    }
+                })
+                .collect();
+
+            if is_matched {
+                let station = settings_edit.apply(StationParameters { storage, ..station });
+                building.with_parameters_view(&station)
+            } else {
+                Ok(building)
+            }
+        })
+        .collect()
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parameters::{ParameterView, station::STATION_PARAMETERS_LEN};
+
+    fn new_station(item: Item, uuid: u128, storage: &[(i32, LogisticRole)]) -> Building {
+        let station = StationParameters {
+            storage: storage
+                .iter()
+                .map(|&(item_id, role)| StationStorage {
+                    item_id,
+                    local_role: role,
+                    remote_role: role,
+                    max: 5000,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let mut parameters = vec![0; STATION_PARAMETERS_LEN];
+        station.write(&mut parameters);
+        Building {
+            uuid: Some(uuid),
+            item_id: item.into(),
+            parameters,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_edit_storage() {
+        let buildings = vec![
+            new_station(
+                Item::行星内物流运输站,
+                0,
+                &[(1101, LogisticRole::Supply), (1104, LogisticRole::Demand)],
+            ),
+            new_station(Item::星际物流运输站, 1, &[(1104, LogisticRole::Demand)]),
+            Building {
+                uuid: Some(2),
+                item_id: Item::传送带.into(),
+                ..Default::default()
+            },
+            new_station(Item::轨道采集器, 3, &[(1104, LogisticRole::Demand)]),
+        ];
+        let collector = buildings.last().map(|building| building.parameters.clone());
+        let filter = StorageFilter {
+            item_id: Some(1104),
+            local_role: Some(LogisticRole::Demand),
+            ..Default::default()
+        };
+
+        let matches = query_storage(&buildings, &filter);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|found| (found.uuid, found.index))
+                .collect::<Vec<_>>(),
+            vec![(Some(0), 2), (Some(1), 1)]
+        );
+
+        let edited = edit_storage(
+            buildings,
+            &StorageFilter {
+                station: Some(Item::星际物流运输站),
+                ..filter
+            },
+            &StorageEdit {
+                remote_role: Some(LogisticRole::Supply),
+                max: Some(10000),
+                ..Default::default()
+            },
+            &StationSettingsEdit {
+                trip_range_drones: Some(60.0),
+                trip_range_ships: Some(2.5),
+                warp_enable_distance: Some(0.5),
+                warper_necessary: Some(true),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let stations = edited
+            .iter()
+            .filter_map(station_parameters)
+            .collect::<Vec<_>>();
+        let [pls, ils] = stations.as_slice() else {
+            panic!("unexpected stations count");
+        };
+        assert!(!pls.warper_necessary);
+        assert_eq!(
+            pls.storage.get(1).map(|storage| storage.remote_role),
+            Some(LogisticRole::Demand)
+        );
+        assert!(ils.warper_necessary);
+        assert_eq!(ils.trip_range_drones, 50_000_000);
+        assert_eq!(ils.trip_range_ships, 6_000_000);
+        assert_eq!(ils.warp_enable_distance, 20_000);
+        assert_eq!(
+            ils.storage.first(),
+            Some(&StationStorage {
+                item_id: 1104,
+                local_role: LogisticRole::Demand,
+                remote_role: LogisticRole::Supply,
+                max: 10000,
+            })
+        );
+        // 其他物品格和参数的长度保持不变
+        assert_eq!(ils.storage.get(1), Some(&StationStorage::default()));
+        assert!(
+            edited
+                .iter()
+                .take(2)
+                .all(|building| building.parameters.len() == STATION_PARAMETERS_LEN)
+        );
+        // 轨道采集器不被修改
+        assert_eq!(
+            edited.last().map(|building| building.parameters.clone()),
+            collector
+        );
+    }
+
+    #[test]
+    fn test_edit_storage_keeps_switches() {
+        // 游戏中关闭的开关储存为-1，这里是设置中的包含轨道采集器和必须装备翘曲器
+        let mut station = new_station(Item::星际物流运输站, 0, &[(1104, LogisticRole::Demand)]);
+        station
+            .parameters
+            .iter_mut()
+            .skip(323)
+            .step_by(2)
+            .take(2)
+            .for_each(|switch| *switch = -1);
+        let edit = |building: Building, switch: bool| {
+            edit_storage(
+                vec![building],
+                &StorageFilter::default(),
+                &StorageEdit::default(),
+                &StationSettingsEdit {
+                    include_orbit_collector: Some(switch),
+                    warper_necessary: Some(switch),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap()
+            .parameters
+        };
+
+        let parsed = station_parameters(&station).unwrap();
+        assert!(!parsed.include_orbit_collector);
+        assert!(!parsed.warper_necessary);
+
+        assert_eq!(edit(station.clone(), false), station.parameters);
+
+        let on = Building {
+            parameters: edit(station.clone(), true),
+            ..station.clone()
+        };
+        let parsed = station_parameters(&on).unwrap();
+        assert!(parsed.include_orbit_collector);
+        assert!(parsed.warper_necessary);
+
+        assert_eq!(edit(on, false), station.parameters);
+    }
+}
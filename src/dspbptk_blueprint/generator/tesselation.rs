@@ -57,7 +57,7 @@ impl Module {
 }
 
 /// 代表了一行的建筑数据，
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Row {
     /// 这一行的建筑类型，也就是建筑在输入数组中对应的下标
     pub module_type: Module,
@@ -70,6 +70,7 @@ pub struct Row {
 }
 
 /// 代表了一个缓存了重要数据的中间布局
+#[derive(Debug, Clone)]
 pub struct Draft {
     pub pizza_count: f64,
     pub rows: Vec<Row>,
@@ -106,6 +107,55 @@ impl Draft {
             false
         }
     }
+
+    /// 推入`modules`中下标为`module_type`的模块，同时更新每种模块的数量和分数
+    ///
+    /// 与[`Draft::push`]不同，这一行的最高点越过极点时不会推入
+    #[must_use]
+    pub fn push_module(&mut self, modules: &[Module], module_type: usize, need: &[f64]) -> bool {
+        let Some(module) = modules.get(module_type) else {
+            return false;
+        };
+        let bottom_y = self.rows.last().map_or(0.0, |row| row.top_y);
+        if module
+            .calculate_next_edge_y(bottom_y)
+            .is_none_or(|top_y| top_y > FRAC_PI_2)
+            || !self.push(module.clone())
+        {
+            return false;
+        }
+
+        if self.each_type_count.len() < modules.len() {
+            self.each_type_count.resize(modules.len(), 0.0);
+        }
+        if let (Some(type_count), Some(row)) =
+            (self.each_type_count.get_mut(module_type), self.rows.last())
+        {
+            #[expect(clippy::cast_precision_loss)]
+            let row_count = row.count as f64;
+            *type_count += row_count;
+        }
+        self.score = score(&self.each_type_count, need);
+        true
+    }
+
+    /// 单位面积上的分数，用于比较高度不同的中间布局
+    ///
+    /// 分数相同时（比如还有模块没有放置，分数都是0）比较所有模块相对需求的倍率之和
+    fn density(&self, need: &[f64]) -> (f64, f64) {
+        let area = self.rows.last().map_or(0.0, |row| row.top_y.sin());
+        let total = self
+            .each_type_count
+            .iter()
+            .zip(need.iter())
+            .map(|(module, need)| module / need)
+            .filter(|ratio| ratio.is_finite())
+            .sum::<f64>();
+        (
+            self.score.filter(|score| score.is_finite()).unwrap_or(0.0) / area,
+            total / area,
+        )
+    }
 }
 
 /// 这个函数不检查y是否超标，超标解应该放在流程控制中排除
@@ -122,8 +172,122 @@ pub fn score(each_type_count: &[f64], need: &[f64]) -> Option<f64> {
 // TODO 基于排列生成具体的蓝图，这个函数必须抽象出去，不然难以保证基于相同的基因生成的蓝图结果一致
 // 而且好像还需要考虑渐进式生成，根据已有排列和缓存信息生成下一列
 
-// 输入每种模块的需求比例，纬度限制，输出排列
+/// 集束搜索每一层保留的中间布局数量
+const BEAM_WIDTH: usize = 64;
+
+/// 输入每种模块和它们的需求比例，输出从赤道到极点每一行的模块类型（模块在`modules`中的下标）
+///
+/// 每一行只放一种模块，行的高度和数量由[`Draft::push_module`]计算，直到纬度限制内再也放不下任何模块。
+/// 目标是最大化[`score`]，也就是让最缺的模块尽量多\
+/// 搜索空间随行数指数增长，这里用集束搜索：每一层只保留单位面积分数最高的[`BEAM_WIDTH`]个中间布局，
+/// 因此结果不保证是最优解\
+/// 同样的输入总是得到同样的排列，用[`Draft::push_module`]按顺序重放即可得到完整的布局
 #[must_use]
-pub fn tesselation(_modules: &[Module], _need: &[f64]) -> Vec<usize> {
-    todo!()
+pub fn tesselation(modules: &[Module], need: &[f64], pizza_count: f64) -> Vec<usize> {
+    let mut beam = vec![(Draft::new(pizza_count), Vec::new())];
+    let mut best: Option<(Draft, Vec<usize>)> = None;
+
+    while !beam.is_empty() {
+        let mut next = Vec::new();
+        for (draft, arrangement) in beam {
+            let children = (0..modules.len())
+                .filter_map(|module_type| {
+                    let mut child = draft.clone();
+                    child.push_module(modules, module_type, need).then(|| {
+                        let arrangement = [arrangement.as_slice(), &[module_type]].concat();
+                        (child, arrangement)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            // 放不下任何模块了，分数不会再增加
+            if children.is_empty() {
+                let score_of = |draft: &Draft| draft.score.unwrap_or(f64::NEG_INFINITY);
+                if best
+                    .as_ref()
+                    .is_none_or(|(best, _)| score_of(&draft) > score_of(best))
+                {
+                    best = Some((draft, arrangement));
+                }
+            } else {
+                next.extend(children);
+            }
+        }
+
+        next.sort_by(|(a, _), (b, _)| {
+            let (a_score, a_total) = a.density(need);
+            let (b_score, b_total) = b.density(need);
+            b_score
+                .total_cmp(&a_score)
+                .then(b_total.total_cmp(&a_total))
+        });
+        next.truncate(BEAM_WIDTH);
+        beam = next;
+    }
+
+    best.map(|(_, arrangement)| arrangement).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn replay(modules: &[Module], need: &[f64], arrangement: &[usize]) -> Draft {
+        let mut draft = Draft::new(4.0);
+        for &module_type in arrangement {
+            assert!(draft.push_module(modules, module_type, need));
+        }
+        draft
+    }
+
+    #[test]
+    fn test_tesselation_single_module() {
+        let modules = [Module::new(6.0, 6.0)];
+        let mut draft = Draft::new(4.0);
+        while draft.push_module(&modules, 0, &[1.0]) {}
+
+        let arrangement = tesselation(&modules, &[1.0], 4.0);
+
+        assert_eq!(arrangement, vec![0; draft.rows.len()]);
+        assert!(draft.rows.iter().all(|row| row.top_y <= FRAC_PI_2));
+    }
+
+    #[test]
+    fn test_tesselation_mixed_modules() {
+        let modules = [Module::new(7.3, 4.2), Module::new(3.0, 3.0)];
+        let need = [1.0, 2.0];
+
+        let arrangement = tesselation(&modules, &need, 4.0);
+        let draft = replay(&modules, &need, &arrangement);
+        let score = draft.score.unwrap_or(0.0);
+
+        assert!(score > 0.0);
+        assert!(arrangement.contains(&0) && arrangement.contains(&1));
+
+        // 不比简单交替的排列差
+        let alternate = (0..arrangement.len() * 2)
+            .map(|i| i % 2)
+            .scan(Draft::new(4.0), |draft, module_type| {
+                draft
+                    .push_module(&modules, module_type, &need)
+                    .then(|| draft.score.unwrap_or(0.0))
+            })
+            .last()
+            .unwrap_or(0.0);
+        assert!(score >= alternate, "{score} < {alternate}");
+    }
+
+    #[test]
+    fn test_push_keeps_row_past_pole() {
+        // pv.rs和photon.rs依赖`push`原有的行为：最后一行的最高点可以越过极点
+        let modules = [Module::new(0.5, 5.0)];
+        let mut draft = Draft::new(4.0);
+        while draft.push(modules[0].clone()) {}
+        let mut draft_module = Draft::new(4.0);
+        while draft_module.push_module(&modules, 0, &[1.0]) {}
+
+        assert_eq!(draft.rows.len(), 50);
+        assert!(draft.rows.last().is_some_and(|row| row.top_y > FRAC_PI_2));
+        assert_eq!(draft_module.rows.len(), 49);
+    }
 }
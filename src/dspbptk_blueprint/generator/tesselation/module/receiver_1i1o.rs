@@ -1,6 +1,10 @@
 use crate::{
-    dspbptk_blueprint::{Building, editor::belt::connect_belts, uuid::some_new_uuid},
+    dspbptk_blueprint::{Building, editor::belt::connect_belts, uuid::UuidAllocator},
     item::Item,
+    parameters::{
+        ParameterView,
+        power::{RayReceiverMode, RayReceiverParameters},
+    },
 };
 use nalgebra::Vector3;
 
@@ -19,6 +23,7 @@ pub fn new(
     input_from_slot: i8,
     output_obj: &Building,
     output_to_slot: i8,
+    uuids: &mut UuidAllocator,
 ) -> Vec<Building> {
     let (y_scale, sorter_yaw) = if input_obj.local_offset.y > output_obj.local_offset.y {
         (1.0_f64, 180.0_f64)
@@ -28,17 +33,20 @@ pub fn new(
 
     // 光子锅
     let receiver = Building {
-        uuid: some_new_uuid(),
+        uuid: uuids.some_new_uuid(),
         item_id: Item::射线接收站 as i16,
         model_index: RECEIVER_MODEL,
         local_offset,
-        parameters: vec![1208],
+        parameters: RayReceiverParameters {
+            mode: RayReceiverMode::Photon,
+        }
+        .to_parameters(),
         ..Default::default()
     };
 
     // 透镜带
     let belt_lens_from_sorter = Building {
-        uuid: some_new_uuid(),
+        uuid: uuids.some_new_uuid(),
         item_id: Item::极速传送带 as i16,
         model_index: BELT_MODEL,
         local_offset: Vector3::new(
@@ -51,7 +59,7 @@ pub fn new(
     };
 
     let belt_lens_into_receiver = Building {
-        uuid: some_new_uuid(),
+        uuid: uuids.some_new_uuid(),
         item_id: Item::极速传送带 as i16,
         model_index: BELT_MODEL,
         local_offset: Vector3::new(
@@ -65,7 +73,7 @@ pub fn new(
 
     // 分流透镜的黄爪
     let sorter_lens_input = Building {
-        uuid: some_new_uuid(),
+        uuid: uuids.some_new_uuid(),
         item_id: Item::分拣器 as i16,
         model_index: SORTER_MODEL,
         yaw: sorter_yaw,
@@ -90,7 +98,7 @@ pub fn new(
 
     // 光子带
     let belt_photons_from_receiver = Building {
-        uuid: some_new_uuid(),
+        uuid: uuids.some_new_uuid(),
         item_id: Item::极速传送带 as i16,
         model_index: BELT_MODEL,
         local_offset: Vector3::new(
@@ -103,7 +111,7 @@ pub fn new(
     };
 
     let belt_photons_output = Building {
-        uuid: some_new_uuid(),
+        uuid: uuids.some_new_uuid(),
         item_id: Item::极速传送带 as i16,
         model_index: BELT_MODEL,
         local_offset: Vector3::new(
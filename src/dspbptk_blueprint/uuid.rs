@@ -1,4 +1,5 @@
-use uuid::Uuid;
+use std::collections::HashSet;
+use uuid::{Builder, Uuid};
 
 use crate::{blueprint::Building, error::DspbptkError};
 
@@ -24,12 +25,112 @@ pub fn uuid_try_from_index(index: i32) -> Result<Option<u128>, DspbptkError> {
     }
 }
 
-#[must_use]
-pub fn new_uuid() -> u128 {
-    Uuid::new_v4().to_u128_le()
+/// 生成uuid的上下文，在编辑器和生成器之间传递
+///
+/// * 伪随机数由种子决定，相同的种子和相同的调用顺序总是得到相同的uuid，便于回归测试
+/// * 记录所有已分配或保留的uuid，新的uuid保证不与它们重复，而不只是“几乎不可能重复”
+#[derive(Debug, Clone)]
+pub struct UuidAllocator {
+    state: u64,
+    used: HashSet<u128>,
 }
 
-#[must_use]
-pub fn some_new_uuid() -> Option<u128> {
-    Some(Uuid::new_v4().to_u128_le())
+impl UuidAllocator {
+    #[must_use]
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            state: seed,
+            used: HashSet::new(),
+        }
+    }
+
+    /// 没有指定种子时使用随机的种子，此时每次运行的结果都不同
+    #[must_use]
+    pub fn new(seed: Option<u64>) -> Self {
+        Self::from_seed(seed.unwrap_or_else(|| Uuid::new_v4().as_u64_pair().0))
+    }
+
+    /// 保留已经存在的uuid，之后分配的uuid不会与它们重复
+    pub fn reserve(&mut self, uuids: impl IntoIterator<Item = u128>) {
+        self.used.extend(uuids);
+    }
+
+    /// `SplitMix64`
+    const fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let z = self.state;
+        let z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        let z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    const fn next_u128(&mut self) -> u128 {
+        ((self.next_u64() as u128) << 64) | self.next_u64() as u128
+    }
+
+    /// 分配一个新的v4 uuid
+    pub fn new_uuid(&mut self) -> u128 {
+        loop {
+            let uuid = Builder::from_random_bytes(self.next_u128().to_le_bytes())
+                .into_uuid()
+                .to_u128_le();
+            if self.used.insert(uuid) {
+                return uuid;
+            }
+        }
+    }
+
+    pub fn some_new_uuid(&mut self) -> Option<u128> {
+        Some(self.new_uuid())
+    }
+
+    /// 为模块的一个副本分配uuid的偏移量，副本中的每个uuid都加上这个偏移量，见[`Building::offset`]
+    ///
+    /// 保证偏移后的所有uuid都未被使用，并保留它们
+    ///
+    /// [`Building::offset`]: crate::dspbptk_blueprint::Building::offset
+    pub fn new_offset(&mut self, uuids: &[u128]) -> u128 {
+        loop {
+            let offset = self.next_u128();
+            let mut shifted = uuids.iter().map(|uuid| uuid.wrapping_add(offset));
+            if shifted.all(|uuid| !self.used.contains(&uuid)) {
+                self.reserve(uuids.iter().map(|uuid| uuid.wrapping_add(offset)));
+                return offset;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_uuid_allocator_is_deterministic() {
+        let draw = |seed| {
+            let mut uuids = UuidAllocator::from_seed(seed);
+            let uuid = uuids.new_uuid();
+            (uuid, uuids.new_offset(&[0, 1, 2]))
+        };
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+    }
+
+    #[test]
+    fn test_uuid_allocator_avoids_collisions() {
+        let mut expected = UuidAllocator::from_seed(0);
+        let first = expected.new_uuid();
+
+        // 种子相同时，第一次抽到的uuid已经被保留，只能换一个
+        let mut uuids = UuidAllocator::from_seed(0);
+        uuids.reserve([first]);
+        let uuid = uuids.new_uuid();
+        assert_ne!(uuid, first);
+        assert_eq!(Uuid::from_u128_le(uuid).get_version_num(), 4);
+
+        let first_offset = UuidAllocator::from_seed(1).new_offset(&[0, 1]);
+        let mut offsets = UuidAllocator::from_seed(1);
+        offsets.reserve([first_offset.wrapping_add(1)]);
+        assert_ne!(offsets.new_offset(&[0, 1]), first_offset);
+    }
 }
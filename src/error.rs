@@ -30,10 +30,34 @@ pub enum DspbptkError {
     UnexpectBuildingsCount(<u32 as TryFrom<usize>>::Error),
     #[error("unexpect parameters length: {0}")]
     UnexpectParametersLength(<u16 as TryFrom<usize>>::Error),
+    #[error("unexpect building kind: item_id = {0}, kind = {1:?}")]
+    UnexpectBuildingKind(i16, crate::item::BuildingKind),
     #[error("out range uuid: {0}")]
     TryFromUuidError(std::num::TryFromIntError),
     #[error("out range index: {0}")]
     TryFromIndexError(std::num::TryFromIntError),
+    #[error("unexpect header field {0}: {1:?}")]
+    UnexpectHeaderField(&'static str, String),
+    #[error("broken url encoding: {0:?}")]
+    BrokenUrlEncoding(String),
+    #[error("warn as error: {0}")]
+    DeniedWarn(DspbptkWarn),
+    #[error("{path:?}: {source}")]
+    InFile {
+        path: std::path::PathBuf,
+        source: Box<Self>,
+    },
+}
+
+impl DspbptkError {
+    /// 附加出错的文件路径
+    #[must_use]
+    pub fn in_file(self, path: &std::path::Path) -> Self {
+        Self::InFile {
+            path: path.to_path_buf(),
+            source: Box::new(self),
+        }
+    }
 }
 
 #[derive(Error, Debug, Eq, PartialEq, Clone)]
@@ -50,6 +74,22 @@ pub enum DspbptkWarn {
     UnknownAfterHeader,
     #[error("unexpected MD5F: expected = {0:?}, actual = {1:?}")]
     UnexpectedMD5F(String, String),
+    #[error("lossy legacy version: index = {0}, item_id = {1}")]
+    LossyLegacyVersion(i32, i16),
+    #[error("dropped dangling {link:?} reference: index = {index}, item_id = {item_id}")]
+    DroppedReference {
+        /// 建筑在输入中的index
+        index: i32,
+        item_id: i16,
+        link: crate::graph::Reference,
+    },
+    #[error("dropped dangling {link:?} reference: uuid = {uuid:?}, item_id = {item_id}")]
+    DroppedUuidReference {
+        /// 建筑在输入中的uuid
+        uuid: Option<u128>,
+        item_id: i16,
+        link: crate::graph::Reference,
+    },
 }
 
 #[derive(Error, Debug, PartialEq, Clone)]
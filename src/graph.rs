@@ -0,0 +1,297 @@
+use crate::{blueprint, dspbptk_blueprint};
+use petgraph::{
+    Direction,
+    algo::tarjan_scc,
+    graph::{DiGraph, NodeIndex},
+    unionfind::UnionFind,
+};
+use std::{collections::HashMap, hash::Hash};
+
+/// 建筑之间通过`temp_output_obj_idx`和`temp_input_obj_idx`形成的连接
+pub trait Connection {
+    type Id: Copy + Eq + Hash;
+
+    /// 建筑自身的编号，没有编号的建筑不会出现在图中
+    fn id(&self) -> Option<Self::Id>;
+
+    /// 输出对象的编号，以及(自身的接口, 输出对象的接口)
+    fn output(&self) -> Option<(Self::Id, Link)>;
+
+    /// 输入对象的编号，以及(输入对象的接口, 自身的接口)
+    fn input(&self) -> Option<(Self::Id, Link)>;
+}
+
+/// 边上的接口信息，`from_slot`是上游建筑的接口，`to_slot`是下游建筑的接口
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Link {
+    pub from_slot: i8,
+    pub to_slot: i8,
+}
+
+/// 悬空引用的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reference {
+    Output,
+    Input,
+}
+
+/// 指向了蓝图中不存在的建筑的引用
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dangling<Id> {
+    pub building: Id,
+    pub reference: Reference,
+    pub target: Id,
+}
+
+impl Connection for blueprint::Building {
+    type Id = i32;
+
+    fn id(&self) -> Option<i32> {
+        (self.index != Self::INDEX_NULL).then_some(self.index)
+    }
+
+    fn output(&self) -> Option<(i32, Link)> {
+        (self.temp_output_obj_idx != Self::INDEX_NULL).then_some((
+            self.temp_output_obj_idx,
+            Link {
+                from_slot: self.output_from_slot,
+                to_slot: self.output_to_slot,
+            },
+        ))
+    }
+
+    fn input(&self) -> Option<(i32, Link)> {
+        (self.temp_input_obj_idx != Self::INDEX_NULL).then_some((
+            self.temp_input_obj_idx,
+            Link {
+                from_slot: self.input_from_slot,
+                to_slot: self.input_to_slot,
+            },
+        ))
+    }
+}
+
+impl Connection for dspbptk_blueprint::Building {
+    type Id = u128;
+
+    fn id(&self) -> Option<u128> {
+        self.uuid
+    }
+
+    fn output(&self) -> Option<(u128, Link)> {
+        self.temp_output_obj_idx.map(|uuid| {
+            (
+                uuid,
+                Link {
+                    from_slot: self.output_from_slot,
+                    to_slot: self.output_to_slot,
+                },
+            )
+        })
+    }
+
+    fn input(&self) -> Option<(u128, Link)> {
+        self.temp_input_obj_idx.map(|uuid| {
+            (
+                uuid,
+                Link {
+                    from_slot: self.input_from_slot,
+                    to_slot: self.input_to_slot,
+                },
+            )
+        })
+    }
+}
+
+/// 建筑的连接关系，边的方向即物流的方向
+///
+/// 节点上保存的是建筑在输入数组中的下标
+#[derive(Debug, Clone)]
+pub struct ConnectionGraph<Id> {
+    pub graph: DiGraph<usize, Link>,
+    pub nodes: HashMap<Id, NodeIndex>,
+    pub dangling: Vec<Dangling<Id>>,
+}
+
+impl<Id: Copy + Eq + Hash> ConnectionGraph<Id> {
+    /// 建立连接图，同一对建筑之间的输出和输入引用会合并为一条边
+    #[must_use]
+    pub fn new<B: Connection<Id = Id>>(buildings: &[B]) -> Self {
+        let mut graph = DiGraph::new();
+        let nodes = buildings
+            .iter()
+            .enumerate()
+            .filter_map(|(i, building)| building.id().map(|id| (id, graph.add_node(i))))
+            .collect::<HashMap<_, _>>();
+
+        let references = buildings.iter().filter_map(|building| {
+            building.id().map(|id| {
+                let output = building
+                    .output()
+                    .map(|(target, link)| (id, Reference::Output, target, link));
+                let input = building
+                    .input()
+                    .map(|(target, link)| (id, Reference::Input, target, link));
+                output.into_iter().chain(input)
+            })
+        });
+
+        let mut dangling = Vec::new();
+        for (id, reference, target, link) in references.flatten() {
+            match (nodes.get(&id), nodes.get(&target)) {
+                (Some(&node), Some(&target_node)) => {
+                    let (from, to) = match reference {
+                        Reference::Output => (node, target_node),
+                        Reference::Input => (target_node, node),
+                    };
+                    graph.update_edge(from, to, link);
+                }
+                _ => dangling.push(Dangling {
+                    building: id,
+                    reference,
+                    target,
+                }),
+            }
+        }
+
+        Self {
+            graph,
+            nodes,
+            dangling,
+        }
+    }
+
+    fn neighbors(&self, id: Id, direction: Direction) -> Vec<usize> {
+        self.nodes.get(&id).map_or_else(Vec::new, |&node| {
+            self.graph
+                .neighbors_directed(node, direction)
+                .filter_map(|neighbor| self.graph.node_weight(neighbor).copied())
+                .collect()
+        })
+    }
+
+    /// 直接向`id`输入的建筑的下标
+    #[must_use]
+    pub fn upstream(&self, id: Id) -> Vec<usize> {
+        self.neighbors(id, Direction::Incoming)
+    }
+
+    /// `id`直接输出到的建筑的下标
+    #[must_use]
+    pub fn downstream(&self, id: Id) -> Vec<usize> {
+        self.neighbors(id, Direction::Outgoing)
+    }
+
+    /// 忽略方向的连通分量，每个分量是建筑下标的数组，按最小下标排序
+    #[must_use]
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut union_find = UnionFind::<usize>::new(self.graph.node_count());
+        for edge in self.graph.raw_edges() {
+            union_find.union(edge.source().index(), edge.target().index());
+        }
+
+        let mut components = self
+            .graph
+            .node_indices()
+            .fold(HashMap::<_, Vec<_>>::new(), |mut components, node| {
+                if let Some(&i) = self.graph.node_weight(node) {
+                    components
+                        .entry(union_find.find(node.index()))
+                        .or_default()
+                        .push(i);
+                }
+                components
+            })
+            .into_values()
+            .map(|mut component| {
+                component.sort_unstable();
+                component
+            })
+            .collect::<Vec<_>>();
+        components.sort_unstable();
+        components
+    }
+
+    /// 连接中的环，每个环是一个强连通分量中建筑的下标，包括自己连接自己的建筑
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        tarjan_scc(&self.graph)
+            .into_iter()
+            .filter(|scc| match scc.as_slice() {
+                [node] => self.graph.contains_edge(*node, *node),
+                _ => true,
+            })
+            .map(|scc| {
+                let mut cycle = scc
+                    .into_iter()
+                    .filter_map(|node| self.graph.node_weight(node).copied())
+                    .collect::<Vec<_>>();
+                cycle.sort_unstable();
+                cycle
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn belt(index: i32, output: i32, input: i32) -> blueprint::Building {
+        blueprint::Building {
+            index,
+            temp_output_obj_idx: output,
+            temp_input_obj_idx: input,
+            output_to_slot: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_connection_graph() {
+        let buildings = [
+            belt(0, 1, -1),
+            belt(1, 2, 0),
+            belt(2, 1, -1),
+            belt(3, 9, -1),
+            belt(4, -1, -1),
+        ];
+        let graph = ConnectionGraph::new(&buildings);
+
+        assert_eq!(graph.graph.edge_count(), 3);
+        assert_eq!(graph.downstream(0), [1]);
+        assert_eq!(graph.upstream(1).len(), 2);
+        assert_eq!(graph.components(), [vec![0, 1, 2], vec![3], vec![4]]);
+        assert_eq!(graph.cycles(), [vec![1, 2]]);
+        assert_eq!(
+            graph.dangling,
+            [Dangling {
+                building: 3,
+                reference: Reference::Output,
+                target: 9,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_connection_graph_dspbptk() {
+        let buildings = [
+            dspbptk_blueprint::Building {
+                uuid: Some(10),
+                temp_output_obj_idx: Some(20),
+                ..Default::default()
+            },
+            dspbptk_blueprint::Building {
+                uuid: Some(20),
+                temp_input_obj_idx: Some(10),
+                ..Default::default()
+            },
+        ];
+        let graph = ConnectionGraph::new(&buildings);
+
+        assert_eq!(graph.graph.edge_count(), 1);
+        assert_eq!(graph.downstream(10), [1]);
+        assert!(graph.cycles().is_empty());
+        assert!(graph.dangling.is_empty());
+    }
+}
@@ -1,7 +1,7 @@
-use num_enum::IntoPrimitive;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 use strum_macros::EnumIter;
 
-#[derive(Debug, EnumIter, IntoPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, IntoPrimitive, TryFromPrimitive)]
 #[repr(i16)]
 pub enum Item {
     地基 = 1131,
@@ -261,3 +261,141 @@ impl Item {
         }
     }
 }
+
+/// 建筑的大类，决定了建筑在蓝图中的编码方式，以及编辑器如何处理建筑的接口和参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildingKind {
+    Foundation,
+    Belt,
+    Sorter,
+    Splitter,
+    Piler,
+    TrafficMonitor,
+    Sprayer,
+    Storage,
+    Tank,
+    Assembler,
+    Fractionator,
+    Lab,
+    Miner,
+    Dispenser,
+    Station,
+    Power,
+    PowerExchanger,
+    RayReceiver,
+    EmRailEjector,
+    VerticalLaunchingSilo,
+    Turret,
+    Defense,
+    /// 不是建筑，或者是未知的`item_id`
+    Other,
+}
+
+impl BuildingKind {
+    /// 游戏按`item_id`的范围决定传送带和分拣器的编码方式，未知的`item_id`也是如此
+    const BELT_ID: std::ops::RangeInclusive<i16> = 2001..=2009;
+    const SORTER_ID: std::ops::RangeInclusive<i16> = 2011..=2019;
+
+    /// 由`item_id`得到建筑类型
+    ///
+    /// 未知的`item_id`落在传送带或分拣器的范围内时，按游戏的规则归类为传送带或分拣器，
+    /// 以保证编码方式与游戏一致；其他未知的`item_id`归类为[`Self::Other`]，`item_id`本身保持不变
+    #[must_use]
+    pub fn from_item_id(item_id: i16) -> Self {
+        match Item::try_from(item_id) {
+            Ok(item) => Self::from(item),
+            Err(_) if Self::BELT_ID.contains(&item_id) => Self::Belt,
+            Err(_) if Self::SORTER_ID.contains(&item_id) => Self::Sorter,
+            Err(_) => Self::Other,
+        }
+    }
+}
+
+impl From<Item> for BuildingKind {
+    fn from(item: Item) -> Self {
+        match item {
+            Item::地基 => Self::Foundation,
+            Item::传送带 | Item::高速传送带 | Item::极速传送带 => Self::Belt,
+            Item::分拣器 | Item::高速分拣器 | Item::极速分拣器 | Item::集装分拣器 => {
+                Self::Sorter
+            }
+            Item::四向分流器 => Self::Splitter,
+            Item::自动集装机 => Self::Piler,
+            Item::流速监测器 => Self::TrafficMonitor,
+            Item::喷涂机 => Self::Sprayer,
+            Item::小型储物仓 | Item::大型储物仓 => Self::Storage,
+            Item::储液罐 => Self::Tank,
+            Item::制造台mk1
+            | Item::制造台mk2
+            | Item::制造台mk3
+            | Item::重组式制造台
+            | Item::电弧熔炉
+            | Item::位面熔炉
+            | Item::负熵熔炉
+            | Item::原油精炼厂
+            | Item::化工厂
+            | Item::量子化工厂
+            | Item::微型粒子对撞机 => Self::Assembler,
+            Item::分馏塔 => Self::Fractionator,
+            Item::矩阵研究站 | Item::自演化研究站 => Self::Lab,
+            Item::采矿机 | Item::大型采矿机 | Item::抽水站 | Item::原油萃取站 => {
+                Self::Miner
+            }
+            Item::物流配送器 => Self::Dispenser,
+            Item::行星内物流运输站 | Item::星际物流运输站 | Item::轨道采集器 => {
+                Self::Station
+            }
+            Item::电力感应塔
+            | Item::无线输电塔
+            | Item::卫星配电站
+            | Item::风力涡轮机
+            | Item::火力发电厂
+            | Item::微型聚变发电站
+            | Item::地热发电站
+            | Item::太阳能板
+            | Item::蓄电器
+            | Item::蓄电器_满
+            | Item::人造恒星 => Self::Power,
+            Item::能量枢纽 => Self::PowerExchanger,
+            Item::射线接收站 => Self::RayReceiver,
+            Item::电磁轨道弹射器 => Self::EmRailEjector,
+            Item::垂直发射井 => Self::VerticalLaunchingSilo,
+            Item::高斯机枪塔
+            | Item::高频激光塔
+            | Item::聚爆加农炮
+            | Item::磁化电浆炮
+            | Item::导弹防御塔
+            | Item::干扰塔
+            | Item::近程电浆塔 => Self::Turret,
+            Item::信号塔 | Item::行星护盾发生器 | Item::战场分析基站 => {
+                Self::Defense
+            }
+            _ => Self::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_building_kind() {
+        assert_eq!(Item::try_from(2003), Ok(Item::极速传送带));
+        assert_eq!(BuildingKind::from_item_id(2003), BuildingKind::Belt);
+        assert_eq!(BuildingKind::from_item_id(2020), BuildingKind::Splitter);
+        assert_eq!(BuildingKind::from_item_id(1001), BuildingKind::Other);
+
+        // 未知的`item_id`
+        assert!(Item::try_from(2005).is_err());
+        assert_eq!(BuildingKind::from_item_id(2005), BuildingKind::Belt);
+        assert_eq!(BuildingKind::from_item_id(2019), BuildingKind::Sorter);
+        assert_eq!(BuildingKind::from_item_id(9999), BuildingKind::Other);
+
+        // 有模型的物品都是建筑
+        for item in Item::iter().filter(|item| item.model() != ModelIndex::None) {
+            assert_ne!(BuildingKind::from(item), BuildingKind::Other, "{item:?}");
+        }
+    }
+}
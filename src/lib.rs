@@ -23,7 +23,9 @@
 pub mod blueprint;
 pub mod dspbptk_blueprint;
 pub mod error;
+pub mod graph;
 pub mod item;
+pub mod parameters;
 pub mod planet;
 pub mod workflow;
 
@@ -0,0 +1,168 @@
+pub mod logistics;
+pub mod power;
+pub mod station;
+pub mod storage;
+pub mod turret;
+
+use crate::{error::DspbptkError, item::BuildingKind};
+
+/// 建筑参数`parameters`的类型化视图
+///
+/// 视图只解析已知位置上的字段，写回时也只修改这些位置，其他参数原样保留，
+/// 因此“读取后直接写回”总是得到与原来完全相同的参数
+pub trait ParameterView: Sized {
+    /// 视图适用的建筑类型
+    const KINDS: &'static [BuildingKind];
+
+    /// 从参数中读取已知的字段，参数不够长时缺少的部分视为0
+    fn read(item_id: i16, parameters: &[i32]) -> Self;
+
+    /// 把已知的字段写回参数，其他参数保持不变
+    fn write(&self, parameters: &mut Vec<i32>);
+
+    /// 生成新建筑的参数
+    #[must_use]
+    fn to_parameters(&self) -> Vec<i32> {
+        let mut parameters = Vec::new();
+        self.write(&mut parameters);
+        parameters
+    }
+}
+
+fn check_kind<T: ParameterView>(item_id: i16) -> Result<(), DspbptkError> {
+    let kind = BuildingKind::from_item_id(item_id);
+    if T::KINDS.contains(&kind) {
+        Ok(())
+    } else {
+        Err(DspbptkError::UnexpectBuildingKind(item_id, kind))
+    }
+}
+
+/// 读取`item_id`对应建筑的参数
+///
+/// # Errors
+/// 可能的原因：
+/// * 建筑类型与视图不符
+pub fn read_parameters<T: ParameterView>(
+    item_id: i16,
+    parameters: &[i32],
+) -> Result<T, DspbptkError> {
+    check_kind::<T>(item_id)?;
+    Ok(T::read(item_id, parameters))
+}
+
+/// 把视图写回`item_id`对应建筑的参数
+///
+/// # Errors
+/// 可能的原因：
+/// * 建筑类型与视图不符
+pub fn write_parameters<T: ParameterView>(
+    item_id: i16,
+    view: &T,
+    mut parameters: Vec<i32>,
+) -> Result<Vec<i32>, DspbptkError> {
+    check_kind::<T>(item_id)?;
+    view.write(&mut parameters);
+    Ok(parameters)
+}
+
+fn get(parameters: &[i32], index: usize) -> i32 {
+    parameters.get(index).copied().unwrap_or(0)
+}
+
+/// 写入一个参数，参数不够长时只在写入非0值时补0延长
+fn set(parameters: &mut Vec<i32>, index: usize, value: i32) {
+    if parameters.len() <= index && value != 0 {
+        parameters.resize(index + 1, 0);
+    }
+    if let Some(parameter) = parameters.get_mut(index) {
+        *parameter = value;
+    }
+}
+
+/// 读取0/1编码的开关，非0即开
+fn get_flag(parameters: &[i32], index: usize) -> bool {
+    get(parameters, index) != 0
+}
+
+/// 写入0/1编码的开关，开关状态不变时保留原来的非0值
+fn set_flag(parameters: &mut Vec<i32>, index: usize, flag: bool) {
+    if get_flag(parameters, index) != flag {
+        set(parameters, index, i32::from(flag));
+    }
+}
+
+/// 读取游戏用1/-1编码的开关，与游戏一致，大于0才是开
+fn get_switch(parameters: &[i32], index: usize) -> bool {
+    get(parameters, index) > 0
+}
+
+/// 写入1/-1编码的开关，开关状态不变时保留原来的值
+fn set_switch(parameters: &mut Vec<i32>, index: usize, switch: bool) {
+    if get_switch(parameters, index) != switch {
+        set(parameters, index, if switch { 1 } else { -1 });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        power::{RayReceiverMode, RayReceiverParameters},
+        *,
+    };
+    use crate::{blueprint::Building, item::Item};
+
+    #[test]
+    fn test_set() {
+        let mut parameters = vec![1, 2];
+        set(&mut parameters, 3, 0);
+        assert_eq!(parameters, vec![1, 2]);
+        set(&mut parameters, 3, 4);
+        assert_eq!(parameters, vec![1, 2, 0, 4]);
+
+        let mut parameters = vec![0];
+        set_flag(&mut parameters, 0, true);
+        assert_eq!(parameters, vec![1]);
+        set_flag(&mut parameters, 0, false);
+        assert_eq!(parameters, vec![0]);
+
+        // 游戏用-1表示关，状态不变时保持原样，改变时写入1/-1
+        let mut parameters = vec![-1];
+        assert!(!get_switch(&parameters, 0));
+        set_switch(&mut parameters, 0, false);
+        assert_eq!(parameters, vec![-1]);
+        set_switch(&mut parameters, 0, true);
+        assert_eq!(parameters, vec![1]);
+        set_switch(&mut parameters, 0, false);
+        assert_eq!(parameters, vec![-1]);
+    }
+
+    #[test]
+    fn test_check_kind() {
+        assert!(read_parameters::<RayReceiverParameters>(Item::射线接收站 as i16, &[1208]).is_ok());
+        assert!(matches!(
+            read_parameters::<RayReceiverParameters>(Item::传送带 as i16, &[]),
+            Err(DspbptkError::UnexpectBuildingKind(2001, BuildingKind::Belt))
+        ));
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_building_parameters_view() {
+        let receiver = Building {
+            item_id: Item::射线接收站 as i16,
+            ..Default::default()
+        };
+        let photon = RayReceiverParameters {
+            mode: RayReceiverMode::Photon,
+        };
+
+        let receiver = receiver.with_parameters_view(&photon).unwrap();
+        assert_eq!(receiver.parameters, vec![1208]);
+        assert_eq!(receiver.parameters_length, 1);
+        assert_eq!(
+            receiver.parameters_view::<RayReceiverParameters>().unwrap(),
+            photon
+        );
+    }
+}
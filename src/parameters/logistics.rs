@@ -0,0 +1,107 @@
+use crate::{
+    item::BuildingKind,
+    parameters::{ParameterView, get, get_flag, set, set_flag},
+};
+
+/// 四向分流器的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SplitterParameters {
+    /// 每个接口是否优先，接口编号见[`crate::dspbptk_blueprint::editor::mirror`]
+    pub priority: [bool; 4],
+}
+
+impl ParameterView for SplitterParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::Splitter];
+
+    fn read(_item_id: i16, parameters: &[i32]) -> Self {
+        Self {
+            priority: std::array::from_fn(|slot| get_flag(parameters, slot)),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        for (slot, &priority) in self.priority.iter().enumerate() {
+            set_flag(parameters, slot, priority);
+        }
+    }
+}
+
+/// 流速监测器的参数，各种模式和颜色按游戏内的编号原样储存
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TrafficMonitorParameters {
+    /// 目标流量
+    pub target_cargo_amount: i32,
+    /// 监测周期（帧）
+    pub period_tick_count: i32,
+    /// 通过条件，即实际流量与目标流量的比较方式
+    pub pass_operator: i32,
+    /// 通过时的颜色
+    pub pass_color_id: i32,
+    /// 未通过时的颜色
+    pub fail_color_id: i32,
+    /// 监测的货物，0表示所有货物
+    pub cargo_filter: i32,
+    /// 生成货物的模式
+    pub spawn_operator: i32,
+    /// 警报模式
+    pub alarm_mode: i32,
+    /// 系统警报模式
+    pub system_warning_mode: i32,
+    /// 系统警报的图标
+    pub system_warning_signal_id: i32,
+}
+
+impl ParameterView for TrafficMonitorParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::TrafficMonitor];
+
+    fn read(_item_id: i16, parameters: &[i32]) -> Self {
+        Self {
+            target_cargo_amount: get(parameters, 0),
+            period_tick_count: get(parameters, 1),
+            pass_operator: get(parameters, 2),
+            pass_color_id: get(parameters, 3),
+            fail_color_id: get(parameters, 4),
+            cargo_filter: get(parameters, 5),
+            spawn_operator: get(parameters, 6),
+            alarm_mode: get(parameters, 7),
+            system_warning_mode: get(parameters, 8),
+            system_warning_signal_id: get(parameters, 9),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        [
+            self.target_cargo_amount,
+            self.period_tick_count,
+            self.pass_operator,
+            self.pass_color_id,
+            self.fail_color_id,
+            self.cargo_filter,
+            self.spawn_operator,
+            self.alarm_mode,
+            self.system_warning_mode,
+            self.system_warning_signal_id,
+        ]
+        .into_iter()
+        .enumerate()
+        .for_each(|(index, value)| set(parameters, index, value));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_splitter_parameters() {
+        let parameters = vec![0, 1, 0, 0, 42];
+
+        let mut splitter = SplitterParameters::read(0, &parameters);
+        assert_eq!(splitter.priority, [false, true, false, false]);
+
+        splitter.priority = [true, false, false, false];
+        let mut written = parameters;
+        splitter.write(&mut written);
+        assert_eq!(written, vec![1, 0, 0, 0, 42]);
+    }
+}
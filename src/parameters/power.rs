@@ -0,0 +1,128 @@
+use crate::{
+    item::{BuildingKind, Item},
+    parameters::{ParameterView, get, set},
+};
+use num_enum::{FromPrimitive, IntoPrimitive};
+
+/// 射线接收站的工作模式，储存的是产物的`item_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[repr(i32)]
+pub enum RayReceiverMode {
+    /// 直接发电
+    Power = 0,
+    /// 光子生成
+    Photon = Item::临界光子 as i32,
+    #[num_enum(catch_all)]
+    Other(i32),
+}
+
+#[expect(clippy::derivable_impls)]
+impl Default for RayReceiverMode {
+    fn default() -> Self {
+        Self::Power
+    }
+}
+
+/// 射线接收站的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RayReceiverParameters {
+    pub mode: RayReceiverMode,
+}
+
+impl ParameterView for RayReceiverParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::RayReceiver];
+
+    fn read(_item_id: i16, parameters: &[i32]) -> Self {
+        Self {
+            mode: RayReceiverMode::from(get(parameters, 0)),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        set(parameters, 0, self.mode.into());
+    }
+}
+
+/// 电磁轨道弹射器的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EmRailEjectorParameters {
+    /// 发射的目标轨道，0表示未指定
+    pub orbit_id: i32,
+}
+
+impl ParameterView for EmRailEjectorParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::EmRailEjector];
+
+    fn read(_item_id: i16, parameters: &[i32]) -> Self {
+        Self {
+            orbit_id: get(parameters, 0),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        set(parameters, 0, self.orbit_id);
+    }
+}
+
+/// 能量枢纽的工作模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[repr(i32)]
+pub enum PowerExchangerMode {
+    /// 放电
+    Discharge = -1,
+    /// 待机
+    Standby = 0,
+    /// 充电
+    Charge = 1,
+    #[num_enum(catch_all)]
+    Other(i32),
+}
+
+#[expect(clippy::derivable_impls)]
+impl Default for PowerExchangerMode {
+    fn default() -> Self {
+        Self::Standby
+    }
+}
+
+/// 能量枢纽的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PowerExchangerParameters {
+    pub mode: PowerExchangerMode,
+}
+
+impl ParameterView for PowerExchangerParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::PowerExchanger];
+
+    fn read(_item_id: i16, parameters: &[i32]) -> Self {
+        Self {
+            mode: PowerExchangerMode::from(get(parameters, 0)),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        set(parameters, 0, self.mode.into());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_power_parameters() {
+        let photon = RayReceiverParameters {
+            mode: RayReceiverMode::Photon,
+        };
+        assert_eq!(photon.to_parameters(), vec![1208]);
+        assert_eq!(RayReceiverParameters::read(0, &[1208]), photon);
+        assert_eq!(RayReceiverParameters::default().to_parameters(), vec![]);
+
+        // 未知的模式原样保留
+        let exchanger = PowerExchangerParameters::read(0, &[5, 6]);
+        assert_eq!(exchanger.mode, PowerExchangerMode::Other(5));
+        let mut written = vec![5, 6];
+        exchanger.write(&mut written);
+        assert_eq!(written, vec![5, 6]);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    item::{BuildingKind, Item},
+    parameters::{ParameterView, get, get_switch, set, set_switch},
+};
+use num_enum::{FromPrimitive, IntoPrimitive};
+
+/// 游戏生成的物流运输站参数的长度
+pub const STATION_PARAMETERS_LEN: usize = 2048;
+
+// 参数布局：
+// * 0..192：每个物品格占6个参数，依次为物品、本地逻辑、星际逻辑、最大数量，后两个保留
+// * 192..320：每个接口占4个参数，依次为方向、物品格，后两个保留
+// * 320..：运输站的设置
+const STORAGE_OFFSET: usize = 0;
+const STORAGE_STRIDE: usize = 6;
+const MAX_STORAGE_COUNT: usize = 32;
+const SLOTS_OFFSET: usize = 192;
+const SLOT_STRIDE: usize = 4;
+const MAX_SLOTS_COUNT: usize = 32;
+const SETTINGS_OFFSET: usize = 320;
+
+/// 运输机最远路程的缩放：参数中储存的是最远路程对应的角度的余弦乘以这个值
+pub const TRIP_RANGE_DRONES_SCALE: f64 = 1e8;
+/// 1天文单位（AU）的米数
+pub const METERS_PER_AU: f64 = 40_000.0;
+/// 1光年的米数
+pub const METERS_PER_LIGHT_YEAR: f64 = 60.0 * METERS_PER_AU;
+
+/// 物品格的物流逻辑
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[repr(i32)]
+pub enum LogisticRole {
+    /// 仓储
+    Storage = 0,
+    /// 供应
+    Supply = 1,
+    /// 需求
+    Demand = 2,
+    #[num_enum(catch_all)]
+    Other(i32),
+}
+
+// num_enum会把`#[default]`当作`FromPrimitive`的默认值，与`catch_all`冲突，只能手动实现
+#[expect(clippy::derivable_impls)]
+impl Default for LogisticRole {
+    fn default() -> Self {
+        Self::Storage
+    }
+}
+
+/// 接口的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[repr(i32)]
+pub enum SlotDirection {
+    None = 0,
+    /// 从运输站输出到传送带
+    Output = 1,
+    /// 从传送带输入到运输站
+    Input = 2,
+    #[num_enum(catch_all)]
+    Other(i32),
+}
+
+#[expect(clippy::derivable_impls)]
+impl Default for SlotDirection {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StationStorage {
+    pub item_id: i32,
+    pub local_role: LogisticRole,
+    pub remote_role: LogisticRole,
+    pub max: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StationSlot {
+    pub direction: SlotDirection,
+    /// 接口对应的物品格，从1开始，0表示未指定
+    pub storage_index: i32,
+}
+
+/// 物流运输站（包括轨道采集器）的参数
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StationParameters {
+    pub storage: Vec<StationStorage>,
+    pub slots: Vec<StationSlot>,
+    /// 最大充电功率
+    pub work_energy_per_tick: i32,
+    /// 运输机最远路程，储存的是角度的余弦乘以[`TRIP_RANGE_DRONES_SCALE`]
+    pub trip_range_drones: i32,
+    /// 运输船最远路程，单位为米
+    pub trip_range_ships: i32,
+    /// 是否从轨道采集器取货
+    pub include_orbit_collector: bool,
+    /// 曲速启用路程，单位为米
+    pub warp_enable_distance: i32,
+    /// 是否必须装备翘曲器
+    pub warper_necessary: bool,
+    /// 运输机起送量（百分比）
+    pub delivery_drones: i32,
+    /// 运输船起送量（百分比）
+    pub delivery_ships: i32,
+    /// 输出货物集装数量
+    pub piler_count: i32,
+}
+
+impl StationParameters {
+    /// 建筑的物品格数量
+    #[must_use]
+    pub fn storage_count(item_id: i16) -> usize {
+        match Item::try_from(item_id) {
+            Ok(Item::行星内物流运输站) => 4,
+            Ok(Item::星际物流运输站) => 5,
+            Ok(Item::轨道采集器) => 2,
+            _ => 0,
+        }
+    }
+
+    /// 建筑的接口数量
+    #[must_use]
+    pub fn slots_count(item_id: i16) -> usize {
+        match Item::try_from(item_id) {
+            Ok(Item::行星内物流运输站 | Item::星际物流运输站) => 12,
+            _ => 0,
+        }
+    }
+}
+
+impl ParameterView for StationParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::Station];
+
+    fn read(item_id: i16, parameters: &[i32]) -> Self {
+        let storage = (0..Self::storage_count(item_id))
+            .map(|i| {
+                let base = STORAGE_OFFSET + i * STORAGE_STRIDE;
+                StationStorage {
+                    item_id: get(parameters, base),
+                    local_role: LogisticRole::from(get(parameters, base + 1)),
+                    remote_role: LogisticRole::from(get(parameters, base + 2)),
+                    max: get(parameters, base + 3),
+                }
+            })
+            .collect();
+        let slots = (0..Self::slots_count(item_id))
+            .map(|i| {
+                let base = SLOTS_OFFSET + i * SLOT_STRIDE;
+                StationSlot {
+                    direction: SlotDirection::from(get(parameters, base)),
+                    storage_index: get(parameters, base + 1),
+                }
+            })
+            .collect();
+
+        Self {
+            storage,
+            slots,
+            work_energy_per_tick: get(parameters, SETTINGS_OFFSET),
+            trip_range_drones: get(parameters, SETTINGS_OFFSET + 1),
+            trip_range_ships: get(parameters, SETTINGS_OFFSET + 2),
+            include_orbit_collector: get_switch(parameters, SETTINGS_OFFSET + 3),
+            warp_enable_distance: get(parameters, SETTINGS_OFFSET + 4),
+            warper_necessary: get_switch(parameters, SETTINGS_OFFSET + 5),
+            delivery_drones: get(parameters, SETTINGS_OFFSET + 6),
+            delivery_ships: get(parameters, SETTINGS_OFFSET + 7),
+            piler_count: get(parameters, SETTINGS_OFFSET + 8),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        for (i, storage) in self.storage.iter().take(MAX_STORAGE_COUNT).enumerate() {
+            let base = STORAGE_OFFSET + i * STORAGE_STRIDE;
+            set(parameters, base, storage.item_id);
+            set(parameters, base + 1, storage.local_role.into());
+            set(parameters, base + 2, storage.remote_role.into());
+            set(parameters, base + 3, storage.max);
+        }
+        for (i, slot) in self.slots.iter().take(MAX_SLOTS_COUNT).enumerate() {
+            let base = SLOTS_OFFSET + i * SLOT_STRIDE;
+            set(parameters, base, slot.direction.into());
+            set(parameters, base + 1, slot.storage_index);
+        }
+
+        set(parameters, SETTINGS_OFFSET, self.work_energy_per_tick);
+        set(parameters, SETTINGS_OFFSET + 1, self.trip_range_drones);
+        set(parameters, SETTINGS_OFFSET + 2, self.trip_range_ships);
+        set_switch(
+            parameters,
+            SETTINGS_OFFSET + 3,
+            self.include_orbit_collector,
+        );
+        set(parameters, SETTINGS_OFFSET + 4, self.warp_enable_distance);
+        set_switch(parameters, SETTINGS_OFFSET + 5, self.warper_necessary);
+        set(parameters, SETTINGS_OFFSET + 6, self.delivery_drones);
+        set(parameters, SETTINGS_OFFSET + 7, self.delivery_ships);
+        set(parameters, SETTINGS_OFFSET + 8, self.piler_count);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_station_parameters() {
+        let item_id = Item::星际物流运输站 as i16;
+        let parameters = (0..)
+            .take(STATION_PARAMETERS_LEN)
+            .map(|i| i % 7)
+            .collect::<Vec<_>>();
+
+        let mut station = StationParameters::read(item_id, &parameters);
+        assert_eq!(station.storage.len(), 5);
+        assert_eq!(station.slots.len(), 12);
+        assert_eq!(
+            station.storage.get(1),
+            Some(&StationStorage {
+                item_id: 6,
+                local_role: LogisticRole::Storage,
+                remote_role: LogisticRole::Supply,
+                max: 2,
+            })
+        );
+
+        // 读取后直接写回，参数不变
+        let mut written = parameters.clone();
+        station.write(&mut written);
+        assert_eq!(written, parameters);
+
+        if let Some(slot) = station.slots.get_mut(2) {
+            slot.direction = SlotDirection::Input;
+            slot.storage_index = 1;
+        }
+        station.warper_necessary = false;
+        station.write(&mut written);
+        let diff = written
+            .iter()
+            .zip(&parameters)
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, (a, _))| (i, *a))
+            .collect::<Vec<_>>();
+        assert_eq!(diff, vec![(200, 2), (201, 1), (325, -1)]);
+    }
+}
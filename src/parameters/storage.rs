@@ -0,0 +1,50 @@
+use crate::{
+    item::BuildingKind,
+    parameters::{ParameterView, get, get_switch, set, set_switch},
+};
+
+/// 储物仓的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StorageParameters {
+    /// 被禁用的格子数量，即红线之后的格子
+    pub bans: i32,
+}
+
+impl ParameterView for StorageParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::Storage];
+
+    fn read(_item_id: i16, parameters: &[i32]) -> Self {
+        Self {
+            bans: get(parameters, 0),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        set(parameters, 0, self.bans);
+    }
+}
+
+/// 储液罐的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TankParameters {
+    /// 是否允许从上方输出
+    pub output_switch: bool,
+    /// 是否允许从下方输入
+    pub input_switch: bool,
+}
+
+impl ParameterView for TankParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::Tank];
+
+    fn read(_item_id: i16, parameters: &[i32]) -> Self {
+        Self {
+            output_switch: get_switch(parameters, 0),
+            input_switch: get_switch(parameters, 1),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        set_switch(parameters, 0, self.output_switch);
+        set_switch(parameters, 1, self.input_switch);
+    }
+}
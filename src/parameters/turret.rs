@@ -0,0 +1,29 @@
+use crate::{
+    item::BuildingKind,
+    parameters::{ParameterView, get, set},
+};
+
+/// 防御塔的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TurretParameters {
+    /// 编组
+    pub group: i32,
+    /// 攻击目标的设置，按位表示是否攻击地面、太空目标
+    pub vs_settings: i32,
+}
+
+impl ParameterView for TurretParameters {
+    const KINDS: &'static [BuildingKind] = &[BuildingKind::Turret];
+
+    fn read(_item_id: i16, parameters: &[i32]) -> Self {
+        Self {
+            group: get(parameters, 0),
+            vs_settings: get(parameters, 1),
+        }
+    }
+
+    fn write(&self, parameters: &mut Vec<i32>) {
+        set(parameters, 0, self.group);
+        set(parameters, 1, self.vs_settings);
+    }
+}
@@ -2,8 +2,6 @@ use std::f64::consts::PI;
 
 use nalgebra::Vector3;
 
-// TODO 跨纬度的坐标计算
-
 /// 纬线长度(格)
 pub const EQUATORIAL_GRID: f64 = 1000.0;
 
@@ -33,8 +31,6 @@ pub const fn grid_from_arc(arc: f64) -> f64 {
     arc * (HALF_EQUATORIAL_GRID / PI)
 }
 
-// TODO 建筑旋转后的角度修复
-
 // 将方向向量转换为局部偏移
 #[must_use]
 pub fn direction_to_local_offset(direction: &Vector3<f64>, z: f64) -> Vector3<f64> {
@@ -70,6 +66,40 @@ pub fn local_offset_to_direction(local_offset: Vector3<f64>) -> Vector3<f64> {
     )
 }
 
+/// 方向向量所在位置的切平面上，指向北方和东方的单位向量
+///
+/// 在两极处东方没有定义，此时退化为沿经度0°的切向量
+#[must_use]
+pub fn north_east(direction: &Vector3<f64>) -> (Vector3<f64>, Vector3<f64>) {
+    let east = Vector3::z().cross(direction);
+    let east = if east.norm() > f64::EPSILON {
+        east.normalize()
+    } else {
+        Vector3::y()
+    };
+    let north = direction.cross(&east).normalize();
+    (north, east)
+}
+
+/// 将`yaw`（罗盘方位角，0°指向北，90°指向东）转换为切平面上的单位向量
+#[must_use]
+pub fn yaw_to_tangent(direction: &Vector3<f64>, yaw: f64) -> Vector3<f64> {
+    let (north, east) = north_east(direction);
+    let (sin, cos) = yaw.to_radians().sin_cos();
+    north * cos + east * sin
+}
+
+/// 将切平面上的向量转换为`yaw`（罗盘方位角，0°指向北，90°指向东），范围[0, 360)
+#[must_use]
+pub fn tangent_to_yaw(direction: &Vector3<f64>, tangent: &Vector3<f64>) -> f64 {
+    let (north, east) = north_east(direction);
+    tangent
+        .dot(&east)
+        .atan2(tangent.dot(&north))
+        .to_degrees()
+        .rem_euclid(360.0)
+}
+
 // 修复非有限值的情况
 fn fix_value(value: f64, component: f64, default_positive: f64, default_negative: f64) -> f64 {
     if value.is_finite() {
@@ -1,5 +1,8 @@
+pub mod info;
 pub mod io;
+pub mod optimize;
 pub mod process;
+pub mod report;
 
 use clap::ValueEnum;
 
@@ -8,6 +11,17 @@ pub enum BlueprintKind {
     Content(Vec<u8>),
 }
 
+impl BlueprintKind {
+    /// 蓝图写入硬盘后的尺寸
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        match self {
+            Self::Txt(blueprint) => blueprint.len(),
+            Self::Content(content) => content.len(),
+        }
+    }
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 pub enum LegalBlueprintFileType {
     Txt,
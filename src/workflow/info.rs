@@ -0,0 +1,331 @@
+use crate::{
+    blueprint::{Area, Header, data::content::Content},
+    error::{DspbptkError, DspbptkWarn},
+    item::Item,
+    workflow::{BlueprintKind, process::process_front_end_with_decoded_size},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+};
+
+/// 建筑`local_offset`的包围盒
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl BoundingBox {
+    #[expect(clippy::similar_names)]
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        let [x0, y0, z0] = self.min;
+        let [x1, y1, z1] = self.max;
+        let [other_x0, other_y0, other_z0] = other.min;
+        let [other_x1, other_y1, other_z1] = other.max;
+        Self {
+            min: [x0.min(other_x0), y0.min(other_y0), z0.min(other_z0)],
+            max: [x1.max(other_x1), y1.max(other_y1), z1.max(other_z1)],
+        }
+    }
+}
+
+/// 蓝图的统计信息，可以把多张蓝图的统计信息合并
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlueprintInfo {
+    /// 参与统计的蓝图数量
+    pub files: usize,
+    pub buildings: usize,
+    /// 按`item_id`统计的建筑数量
+    pub items: BTreeMap<i16, usize>,
+    /// 按`recipe_id`统计的建筑数量
+    pub recipes: BTreeMap<i16, usize>,
+    pub areas: Vec<Area>,
+    /// 没有建筑时是`None`
+    pub bounding_box: Option<BoundingBox>,
+    pub game_versions: BTreeSet<String>,
+    /// 编码后的尺寸，即蓝图文件本身的尺寸
+    pub encoded_size: usize,
+    /// 解码后的content尺寸
+    pub decoded_size: usize,
+}
+
+impl BlueprintInfo {
+    #[must_use]
+    pub fn new(
+        header: &Header,
+        content: &Content,
+        encoded_size: usize,
+        decoded_size: usize,
+    ) -> Self {
+        let count = |keys: &mut dyn Iterator<Item = i16>| {
+            keys.fold(BTreeMap::new(), |mut map, key| {
+                *map.entry(key).or_insert(0) += 1;
+                map
+            })
+        };
+
+        Self {
+            files: 1,
+            buildings: content.buildings.len(),
+            items: count(&mut content.buildings.iter().map(|building| building.item_id)),
+            recipes: count(&mut content.buildings.iter().map(|building| building.recipe_id)),
+            areas: content.areas.clone(),
+            bounding_box: content
+                .buildings
+                .iter()
+                .map(|building| {
+                    let local_offset = [
+                        building.local_offset_x,
+                        building.local_offset_y,
+                        building.local_offset_z,
+                    ];
+                    BoundingBox {
+                        min: local_offset,
+                        max: local_offset,
+                    }
+                })
+                .reduce(BoundingBox::union),
+            game_versions: std::iter::once(header.game_version.clone())
+                .filter(|game_version| !game_version.is_empty())
+                .collect(),
+            encoded_size,
+            decoded_size,
+        }
+    }
+
+    /// 合并两份统计信息
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.files += other.files;
+        self.buildings += other.buildings;
+        for (item_id, count) in other.items {
+            *self.items.entry(item_id).or_insert(0) += count;
+        }
+        for (recipe_id, count) in other.recipes {
+            *self.recipes.entry(recipe_id).or_insert(0) += count;
+        }
+        self.areas.extend(other.areas);
+        self.bounding_box = match (self.bounding_box, other.bounding_box) {
+            (Some(a), Some(b)) => Some(a.union(b)),
+            (a, b) => a.or(b),
+        };
+        self.game_versions.extend(other.game_versions);
+        self.encoded_size += other.encoded_size;
+        self.decoded_size += other.decoded_size;
+        self
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let items = self
+            .items
+            .iter()
+            .map(|(item_id, count)| {
+                let name = Item::try_from(*item_id).map_or_else(
+                    |_| "null".to_string(),
+                    |item| json_string(&format!("{item:?}")),
+                );
+                format!("{{\"item_id\":{item_id},\"name\":{name},\"count\":{count}}}")
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let recipes = self
+            .recipes
+            .iter()
+            .map(|(recipe_id, count)| format!("{{\"recipe_id\":{recipe_id},\"count\":{count}}}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let areas = self
+            .areas
+            .iter()
+            .map(|area| {
+                format!(
+                    "{{\"index\":{},\"parent_index\":{},\"tropic_anchor\":{},\"area_segments\":{},\"anchor_local_offset_x\":{},\"anchor_local_offset_y\":{},\"width\":{},\"height\":{}}}",
+                    area.index,
+                    area.parent_index,
+                    area.tropic_anchor,
+                    area.area_segments,
+                    area.anchor_local_offset_x,
+                    area.anchor_local_offset_y,
+                    area.width,
+                    area.height
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let bounding_box = self.bounding_box.map_or_else(
+            || "null".to_string(),
+            |bounding_box| {
+                let [x0, y0, z0] = bounding_box.min.map(json_number);
+                let [x1, y1, z1] = bounding_box.max.map(json_number);
+                format!("{{\"min\":[{x0},{y0},{z0}],\"max\":[{x1},{y1},{z1}]}}")
+            },
+        );
+        let game_versions = self
+            .game_versions
+            .iter()
+            .map(|game_version| json_string(game_version))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{{\"files\":{},\"buildings\":{},\"items\":[{items}],\"recipes\":[{recipes}],\"areas\":[{areas}],\"bounding_box\":{bounding_box},\"game_versions\":[{game_versions}],\"encoded_size\":{},\"decoded_size\":{}}}",
+            self.files, self.buildings, self.encoded_size, self.decoded_size
+        )
+    }
+}
+
+impl Display for BlueprintInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "files: {}", self.files)?;
+        let game_versions = self
+            .game_versions
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(f, "game versions: {game_versions}")?;
+        writeln!(f, "encoded size: {}", self.encoded_size)?;
+        writeln!(f, "decoded size: {}", self.decoded_size)?;
+        writeln!(f, "buildings: {}", self.buildings)?;
+        if let Some(bounding_box) = self.bounding_box {
+            let [x0, y0, z0] = bounding_box.min;
+            let [x1, y1, z1] = bounding_box.max;
+            writeln!(f, "bounding box: ({x0}, {y0}, {z0}) - ({x1}, {y1}, {z1})")?;
+        }
+        writeln!(f, "areas:")?;
+        for area in &self.areas {
+            writeln!(
+                f,
+                "  index = {}, parent_index = {}, tropic_anchor = {}, area_segments = {}, anchor = ({}, {}), size = ({}, {})",
+                area.index,
+                area.parent_index,
+                area.tropic_anchor,
+                area.area_segments,
+                area.anchor_local_offset_x,
+                area.anchor_local_offset_y,
+                area.width,
+                area.height
+            )?;
+        }
+        writeln!(f, "items:")?;
+        for (item_id, count) in &self.items {
+            match Item::try_from(*item_id) {
+                Ok(item) => writeln!(f, "  {item:?}({item_id}): {count}")?,
+                Err(_) => writeln!(f, "  {item_id}: {count}")?,
+            }
+        }
+        writeln!(f, "recipes:")?;
+        for (recipe_id, count) in &self.recipes {
+            writeln!(f, "  {recipe_id}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 把字符串转义为JSON字符串
+#[must_use]
+pub fn json_string(string: &str) -> String {
+    let escaped = string
+        .chars()
+        .map(|c| match c {
+            '"' => "\\\"".to_string(),
+            '\\' => "\\\\".to_string(),
+            '\n' => "\\n".to_string(),
+            '\r' => "\\r".to_string(),
+            '\t' => "\\t".to_string(),
+            c if c.is_control() => format!("\\u{:04x}", u32::from(c)),
+            c => c.to_string(),
+        })
+        .collect::<String>();
+    format!("\"{escaped}\"")
+}
+
+/// 把浮点数转换为JSON数字，JSON不能表示的NaN和无穷大输出为`null`
+fn json_number(number: f32) -> String {
+    if number.is_finite() {
+        number.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// 解码蓝图并统计信息
+///
+/// # Errors
+/// 所有读取或解码时发生的错误在此汇总
+pub fn analyse(
+    blueprint: &BlueprintKind,
+) -> Result<(BlueprintInfo, Vec<DspbptkWarn>), DspbptkError> {
+    let (header, content, decoded_size, warns) = process_front_end_with_decoded_size(blueprint)?;
+    Ok((
+        BlueprintInfo::new(&header, &content, blueprint.size(), decoded_size),
+        warns,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blueprint::Building;
+
+    #[test]
+    fn test_merge() {
+        let content = Content {
+            buildings: vec![
+                Building {
+                    item_id: 2003,
+                    local_offset_x: 1.0,
+                    local_offset_y: -2.0,
+                    ..Default::default()
+                },
+                Building {
+                    item_id: 2003,
+                    local_offset_x: -1.0,
+                    local_offset_z: 3.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let info = BlueprintInfo::new(&Header::default(), &content, 10, 20);
+
+        let merged = info.clone().merge(info);
+
+        assert_eq!(merged.files, 2);
+        assert_eq!(merged.buildings, 4);
+        assert_eq!(merged.items, BTreeMap::from([(2003, 4)]));
+        assert_eq!(merged.recipes, BTreeMap::from([(0, 4)]));
+        assert_eq!(
+            merged.bounding_box,
+            Some(BoundingBox {
+                min: [-1.0, -2.0, 0.0],
+                max: [1.0, 0.0, 3.0],
+            })
+        );
+        assert_eq!(merged.encoded_size, 20);
+        assert_eq!(merged.decoded_size, 40);
+    }
+
+    #[test]
+    fn test_to_json_non_finite() {
+        let info = BlueprintInfo {
+            bounding_box: Some(BoundingBox {
+                min: [f32::NAN, f32::NEG_INFINITY, -1.5],
+                max: [f32::INFINITY, 0.0, 2.0],
+            }),
+            ..Default::default()
+        };
+        assert!(
+            info.to_json()
+                .contains("\"bounding_box\":{\"min\":[null,null,-1.5],\"max\":[null,0,2]}")
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\n光"), "\"a\\\"b\\\\c\\n光\"");
+    }
+}
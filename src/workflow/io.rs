@@ -1,10 +1,79 @@
-use std::path::{Path, PathBuf};
+use std::{
+    io::{Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use clap::ValueEnum;
 
 use crate::{
+    blueprint::codec,
     error::DspbptkError::{self, CanNotReadFile, CanNotWriteFile, UnknownFileType},
     workflow::{BlueprintKind, FileType, LegalBlueprintFileType},
 };
 
+/// 写入输出文件的条件
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WriteMode {
+    /// 总是写入
+    #[default]
+    Always,
+    /// 仅当输出比参照蓝图更小时写入，类型不同的蓝图无法比较体积，总是写入
+    Smaller,
+    /// 仅当解码后的数据与参照蓝图不同时写入
+    Changed,
+}
+
+/// 覆写已存在的文件前如何备份
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Backup {
+    /// 不备份
+    #[default]
+    None,
+    /// 在原文件旁边备份为`*.bak`，如`a.txt`备份为`a.txt.bak`
+    Bak,
+    /// 备份到指定的文件夹，保留原文件路径的层级结构
+    Dir(PathBuf),
+}
+
+impl Backup {
+    /// 文件`path`的备份路径，不备份时返回`None`
+    #[must_use]
+    pub fn path_of(&self, path: &Path) -> Option<PathBuf> {
+        match self {
+            Self::None => None,
+            Self::Bak => {
+                let mut file_name = path.file_name()?.to_os_string();
+                file_name.push(".bak");
+                Some(path.with_file_name(file_name))
+            }
+            Self::Dir(dir) => Some(
+                dir.join(
+                    path.components()
+                        .filter(|component| matches!(component, Component::Normal(_)))
+                        .collect::<PathBuf>(),
+                ),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    pub mode: WriteMode,
+    pub backup: Backup,
+    /// 只判断是否会写入，不修改硬盘上的任何文件
+    pub dry_run: bool,
+}
+
+/// 写入的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteOutcome {
+    /// 已写入（`dry_run`时为将会写入），如果覆写前做了备份，附带备份的路径
+    Written(Option<PathBuf>),
+    /// 不满足写入条件，没有写入
+    Skipped,
+}
+
 /// # Errors
 /// 可能的原因：
 /// 无法为将要输出的文件创建父文件夹，一般是权限之类的问题
@@ -18,6 +87,53 @@ pub fn create_father_dir(path: &Path) -> Result<(), DspbptkError> {
     })
 }
 
+/// 用作路径时表示标准输入/输出
+pub const STDIO: &str = "-";
+
+#[must_use]
+pub fn is_stdio(path: &Path) -> bool {
+    path == Path::new(STDIO)
+}
+
+/// 以`BLUEPRINT:`开头的文本视为蓝图字符串，其他数据视为content，首尾的空白字符会被忽略
+#[must_use]
+pub fn classify_blueprint(bytes: Vec<u8>) -> BlueprintKind {
+    match String::from_utf8(bytes) {
+        Ok(string) if string.trim_start().starts_with("BLUEPRINT:") => {
+            BlueprintKind::Txt(string.trim().to_string())
+        }
+        Ok(string) => BlueprintKind::Content(string.into_bytes()),
+        Err(e) => BlueprintKind::Content(e.into_bytes()),
+    }
+}
+
+fn read_stdin() -> Result<BlueprintKind, DspbptkError> {
+    let mut bytes = Vec::new();
+    std::io::stdin()
+        .lock()
+        .read_to_end(&mut bytes)
+        .map_err(|e| CanNotReadFile {
+            path: PathBuf::from(STDIO),
+            source: e,
+        })?;
+    Ok(classify_blueprint(bytes))
+}
+
+fn write_stdout(blueprint_kind: BlueprintKind) -> Result<(), DspbptkError> {
+    let bytes = match blueprint_kind {
+        BlueprintKind::Txt(blueprint) => format!("{blueprint}\n").into_bytes(),
+        BlueprintKind::Content(content) => content,
+    };
+    let mut stdout = std::io::stdout().lock();
+    stdout
+        .write_all(&bytes)
+        .and_then(|()| stdout.flush())
+        .map_err(|e| CanNotWriteFile {
+            path: PathBuf::from(STDIO),
+            source: e,
+        })
+}
+
 #[must_use]
 pub fn classify_file_type(entry: &Path) -> FileType {
     entry
@@ -43,10 +159,16 @@ fn read_blueprint_file(path: &Path) -> Result<String, DspbptkError> {
     })
 }
 
+/// 路径为`-`时从标准输入读取，并自动识别蓝图字符串或content
+///
 /// # Errors
 /// 可能的原因：
 /// * 文件的后缀名不受支持
+/// * 无法读取文件或标准输入
 pub fn read_file(path: &Path) -> Result<BlueprintKind, DspbptkError> {
+    if is_stdio(path) {
+        return read_stdin();
+    }
     match classify_file_type(path) {
         FileType::Blueprint(LegalBlueprintFileType::Txt) => {
             let blueprint_string = read_blueprint_file(path)?;
@@ -76,12 +198,141 @@ fn write_content_file(path: &Path, content: Vec<u8>) -> Result<(), DspbptkError>
     })
 }
 
+/// 解码出header（如果有）和content的二进制数据，用于比较两张蓝图是否相同
+fn decode(blueprint_kind: &BlueprintKind) -> Option<(Option<String>, Vec<u8>)> {
+    match blueprint_kind {
+        BlueprintKind::Txt(string) => {
+            let (blueprint, _) = codec::parse(string).ok()?;
+            let content_bin = codec::content::bin_from_string(blueprint.content).ok()?;
+            Some((Some(blueprint.header.to_string()), content_bin))
+        }
+        BlueprintKind::Content(content_bin) => Some((None, content_bin.clone())),
+    }
+}
+
+fn is_changed(blueprint_kind: &BlueprintKind, reference: &BlueprintKind) -> bool {
+    match (decode(blueprint_kind), decode(reference)) {
+        (Some((header, content_bin)), Some((header_reference, content_bin_reference))) => {
+            let is_header_changed = matches!(
+                (header, header_reference),
+                (Some(header), Some(header_reference)) if header != header_reference
+            );
+            is_header_changed || content_bin != content_bin_reference
+        }
+        _ => true,
+    }
+}
+
+fn should_write(
+    mode: WriteMode,
+    blueprint_kind: &BlueprintKind,
+    reference: &BlueprintKind,
+) -> bool {
+    match (mode, blueprint_kind, reference) {
+        (WriteMode::Smaller, BlueprintKind::Txt(_), BlueprintKind::Txt(_))
+        | (WriteMode::Smaller, BlueprintKind::Content(_), BlueprintKind::Content(_)) => {
+            blueprint_kind.size() < reference.size()
+        }
+        (WriteMode::Changed, _, _) => is_changed(blueprint_kind, reference),
+        (WriteMode::Always | WriteMode::Smaller, _, _) => true,
+    }
+}
+
+fn backup_file(path: &Path, backup_path: &Path) -> Result<(), DspbptkError> {
+    create_father_dir(backup_path)?;
+    std::fs::copy(path, backup_path)
+        .map(|_| ())
+        .map_err(|e| CanNotWriteFile {
+            path: backup_path.to_path_buf(),
+            source: e,
+        })
+}
+
+/// 按`options`写入蓝图，`reference`一般是输入的蓝图，用于判断是否满足写入条件，为`None`时总是写入\
+/// 写入标准输出时不会备份
+///
+/// # Errors
+/// 可能的错误：
+/// * 无法为待写入硬盘的文件或备份创建文件夹，一般是权限之类的问题
+/// * 无法备份已存在的文件
+pub fn write_file_with_options(
+    path: &Path,
+    blueprint_kind: BlueprintKind,
+    reference: Option<&BlueprintKind>,
+    options: &WriteOptions,
+) -> Result<WriteOutcome, DspbptkError> {
+    if !reference.is_none_or(|reference| should_write(options.mode, &blueprint_kind, reference)) {
+        return Ok(WriteOutcome::Skipped);
+    }
+
+    let backup_path = options.backup.path_of(path).filter(|_| path.is_file());
+
+    if !options.dry_run {
+        if let Some(backup_path) = &backup_path {
+            backup_file(path, backup_path)?;
+        }
+        write_file(path, blueprint_kind)?;
+    }
+
+    Ok(WriteOutcome::Written(backup_path))
+}
+
+/// 路径为`-`时写入标准输出，蓝图字符串后会附加换行
+///
 /// # Errors
 /// 可能的错误：
 /// * 无法为待写入硬盘的文件创建文件夹，一般是权限之类的问题
+/// * 无法写入文件或标准输出
 pub fn write_file(path: &Path, blueprint_kind: BlueprintKind) -> Result<(), DspbptkError> {
+    if is_stdio(path) {
+        return write_stdout(blueprint_kind);
+    }
     match blueprint_kind {
         BlueprintKind::Txt(blueprint) => write_blueprint_file(path, blueprint),
         BlueprintKind::Content(content) => write_content_file(path, content),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_backup_path_of() {
+        let path = Path::new("./dir/a.txt");
+        assert_eq!(Backup::None.path_of(path), None);
+        assert_eq!(
+            Backup::Bak.path_of(path),
+            Some(PathBuf::from("./dir/a.txt.bak"))
+        );
+        assert_eq!(
+            Backup::Dir(PathBuf::from("backup")).path_of(path),
+            Some(PathBuf::from("backup/dir/a.txt"))
+        );
+    }
+
+    #[test]
+    fn test_classify_blueprint() {
+        assert!(matches!(
+            classify_blueprint(b"BLUEPRINT:0,0\"abc\"0123\r\n".to_vec()),
+            BlueprintKind::Txt(string) if string == "BLUEPRINT:0,0\"abc\"0123"
+        ));
+        assert!(matches!(
+            classify_blueprint(vec![0, 0, 0, 0, 0xff]),
+            BlueprintKind::Content(content) if content == [0, 0, 0, 0, 0xff]
+        ));
+    }
+
+    #[test]
+    fn test_should_write() {
+        let small = BlueprintKind::Content(vec![1, 2]);
+        let large = BlueprintKind::Content(vec![1, 2, 3]);
+
+        assert!(should_write(WriteMode::Always, &large, &small));
+        assert!(should_write(WriteMode::Smaller, &small, &large));
+        assert!(!should_write(WriteMode::Smaller, &large, &small));
+        assert!(!should_write(WriteMode::Smaller, &small, &small));
+        assert!(should_write(WriteMode::Changed, &small, &large));
+        assert!(!should_write(WriteMode::Changed, &small, &small));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    blueprint::{
+        Version, codec,
+        data::{content::Content, header::Header},
+        editor::{
+            fix_index::fix_buildings_index,
+            sort::{SortStrategy, sort_buildings_by},
+        },
+    },
+    error::{DspbptkError, DspbptkWarn},
+    workflow::BlueprintKind,
+};
+use std::fmt;
+use strum::IntoEnumIterator;
+
+/// 优化结果：胜出的排序策略，以及它相对默认策略节省的尺寸
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptimizeReport {
+    pub strategy: SortStrategy,
+    /// 胜出策略编码后的content长度
+    pub size: usize,
+    /// 默认策略编码后的content长度
+    pub default_size: usize,
+}
+
+impl OptimizeReport {
+    /// 相对默认策略节省的字节数
+    #[must_use]
+    pub const fn saving(&self) -> usize {
+        self.default_size.saturating_sub(self.size)
+    }
+}
+
+impl fmt::Display for OptimizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sort by {:?}: {} bytes, {} bytes smaller than {:?}",
+            self.strategy,
+            self.size,
+            self.saving(),
+            SortStrategy::default()
+        )
+    }
+}
+
+struct Candidate {
+    strategy: SortStrategy,
+    content: Content,
+    warns: Vec<DspbptkWarn>,
+}
+
+fn candidate(content: &Content, strategy: SortStrategy) -> Candidate {
+    let (buildings, warns) =
+        fix_buildings_index(sort_buildings_by(content.buildings.clone(), strategy));
+    Candidate {
+        strategy,
+        content: Content {
+            buildings,
+            ..content.clone()
+        },
+        warns,
+    }
+}
+
+/// 尝试所有排序策略，保留编码后最小的content
+///
+/// 先用flate2快速估计每种策略的尺寸，只有估计最小的`finalists`个策略和默认策略会用zopfli压缩，
+/// 尺寸相同时优先选择默认策略。返回胜出的content、它编码后的字符串、优化结果和重排index时的警告
+///
+/// # Errors
+/// 可能的原因：
+/// * gzip压缩错误，这个错误通常不该出现
+pub fn optimize_content(
+    content: &Content,
+    zopfli_options: &zopfli::Options,
+    version: &Version,
+    preserve_unknown: bool,
+    finalists: usize,
+) -> Result<(Content, String, OptimizeReport, Vec<DspbptkWarn>), DspbptkError> {
+    let encode = |candidate: Candidate| {
+        codec::content::string_from_data(
+            &candidate.content,
+            zopfli_options,
+            version,
+            preserve_unknown,
+        )
+        .map(|string| (string, candidate))
+    };
+
+    // 默认策略总是参与决赛，既作为比较的基准，也保证结果不会比默认策略更大
+    let (default_string, default) = encode(candidate(content, SortStrategy::default()))?;
+    let default_size = default_string.len();
+
+    let mut estimated = SortStrategy::iter()
+        .filter(|&strategy| strategy != SortStrategy::default())
+        .map(|strategy| {
+            let candidate = candidate(content, strategy);
+            codec::content::estimate_string_length(&candidate.content, version, preserve_unknown)
+                .map(|estimate| (estimate, candidate))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    estimated.sort_by_key(|(estimate, _)| *estimate);
+
+    let (string, winner) = estimated
+        .into_iter()
+        .take(finalists.max(1))
+        .map(|(_, candidate)| encode(candidate))
+        .try_fold((default_string, default), |best, next| {
+            next.map(|next| {
+                if next.0.len() < best.0.len() {
+                    next
+                } else {
+                    best
+                }
+            })
+        })?;
+
+    let report = OptimizeReport {
+        strategy: winner.strategy,
+        size: string.len(),
+        default_size,
+    };
+    Ok((winner.content, string, report, winner.warns))
+}
+
+/// 与[`crate::workflow::process::process_back_end`]相同，但会先用[`optimize_content`]寻找最小的建筑顺序，
+/// 只输出txt格式（content格式的尺寸与建筑顺序无关）
+///
+/// # Errors
+/// 所有编码或输出时发生的错误在此汇总
+pub fn process_back_end_optimized(
+    header_data: &Header,
+    content_data: &Content,
+    zopfli_options: &zopfli::Options,
+    version: &Version,
+    preserve_unknown: bool,
+    finalists: usize,
+) -> Result<(BlueprintKind, OptimizeReport, Vec<DspbptkWarn>), DspbptkError> {
+    let (content_data, content_string, report, warns_fix_index) = optimize_content(
+        content_data,
+        zopfli_options,
+        version,
+        preserve_unknown,
+        finalists,
+    )?;
+    let warns = [content_data.check_version(version), warns_fix_index].concat();
+    let header_string = codec::header::serialization(header_data, preserve_unknown);
+    Ok((
+        BlueprintKind::Txt(codec::serialization(&header_string, &content_string)),
+        report,
+        warns,
+    ))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blueprint::Building;
+    use std::num::NonZero;
+
+    #[test]
+    fn test_optimize_content() {
+        let buildings = (0..200)
+            .map(|i| Building {
+                index: i,
+                item_id: if i % 2 == 0 { 2001 } else { 2101 },
+                model_index: if i % 2 == 0 { 35 } else { 58 },
+                #[expect(clippy::cast_precision_loss)]
+                local_offset_x: (i * 37 % 200) as f32 * 1.25,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let content = Content {
+            buildings_length: 200,
+            buildings,
+            ..Default::default()
+        };
+        let zopfli_options = zopfli::Options {
+            iteration_count: NonZero::new(1).unwrap(),
+            ..Default::default()
+        };
+
+        let (optimized, string, report, warns) =
+            optimize_content(&content, &zopfli_options, &Version::Neg101, false, 1).unwrap();
+
+        assert!(warns.is_empty());
+        assert_eq!(report.size, string.len());
+        assert!(report.size <= report.default_size);
+        assert_eq!(optimized.buildings.len(), content.buildings.len());
+        assert_eq!(
+            codec::content::bin_from_string(&string).unwrap(),
+            optimized.to_bin(&Version::Neg101, false)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     blueprint::{
-        self, Building, codec,
+        self, Building, Version, codec,
         data::{content::Content, header::Header},
         editor::{fix_index::fix_buildings_index, sort::sort_buildings},
     },
@@ -15,6 +15,17 @@ use crate::{
 pub fn process_front_end(
     blueprint: &BlueprintKind,
 ) -> Result<(Header, Content, Vec<DspbptkWarn>), DspbptkError> {
+    process_front_end_with_decoded_size(blueprint)
+        .map(|(header_data, content_data, _, warns)| (header_data, content_data, warns))
+}
+
+/// 与[`process_front_end`]相同，同时返回解码后的content尺寸，避免为了统计尺寸再解码一次
+///
+/// # Errors
+/// 所有读取或解码时发生的错误在此汇总
+pub fn process_front_end_with_decoded_size(
+    blueprint: &BlueprintKind,
+) -> Result<(Header, Content, usize, Vec<DspbptkWarn>), DspbptkError> {
     match blueprint {
         BlueprintKind::Txt(blueprint_string) => {
             // let start = std::time::Instant::now();
@@ -30,6 +41,7 @@ pub fn process_front_end(
             Ok((
                 header_data,
                 content_data,
+                blueprint_content_bin.len(),
                 [
                     warns_blueprint.as_slice(),
                     warns_content.as_slice(),
@@ -41,25 +53,61 @@ pub fn process_front_end(
         BlueprintKind::Content(content_bin) => {
             let (content_data, warns_content) = Content::from_bin(content_bin)?;
             let header_data = Header::default();
-            Ok((header_data, content_data, warns_content))
+            Ok((header_data, content_data, content_bin.len(), warns_content))
         }
     }
 }
 
+/// 严格模式下，任何警告都会被视为错误；非严格模式下原样返回所有警告
+///
+/// # Errors
+/// 可能的原因：
+/// * 严格模式下出现了警告，返回第一个警告
+pub fn check_warns(
+    warns: Vec<DspbptkWarn>,
+    strict: bool,
+) -> Result<Vec<DspbptkWarn>, DspbptkError> {
+    match warns.first() {
+        Some(warn) if strict => Err(DspbptkError::DeniedWarn(warn.clone())),
+        _ => Ok(warns),
+    }
+}
+
 pub trait DspbptkMap {
-    fn apply(&self, content_in: Content) -> Content;
+    /// 修改蓝图，修改过程中的警告（如被丢弃的悬空引用）随结果一起返回
+    ///
+    /// # Errors
+    /// 可能的原因：
+    /// * 修改过程中出现了无法转换或无法编码的数据，由具体实现决定
+    fn apply(&self, content_in: Content) -> Result<(Content, Vec<DspbptkWarn>), DspbptkError>;
+}
+
+/// 按顺序依次应用多个修改，遇到第一个错误时停止
+impl<T: DspbptkMap> DspbptkMap for [T] {
+    fn apply(&self, content_in: Content) -> Result<(Content, Vec<DspbptkWarn>), DspbptkError> {
+        self.iter().try_fold(
+            (content_in, Vec::new()),
+            |(content, mut warns), func_args| {
+                let (content, warns_apply) = func_args.apply(content)?;
+                warns.extend(warns_apply);
+                Ok((content, warns))
+            },
+        )
+    }
 }
 
-/// 蓝图工具的中间层，对蓝图应用修改
+/// 蓝图工具的中间层，对蓝图应用修改，修改和重排index时的警告随结果一起返回
+///
+/// # Errors
+/// 所有修改时发生的错误在此汇总
 pub fn process_middle_layer(
     header_data_in: Header,
     content_data_in: Content,
     sorting_buildings: bool,
     rounding_local_offset: bool,
-    func_args: &impl DspbptkMap,
-) -> (blueprint::Header, Content) {
-    let (header_data_out, mut content_data_out) =
-        (header_data_in, func_args.apply(content_data_in));
+    func_args: &(impl DspbptkMap + ?Sized),
+) -> Result<(blueprint::Header, Content, Vec<DspbptkWarn>), DspbptkError> {
+    let (mut content_data_out, mut warns) = func_args.apply(content_data_in)?;
 
     if rounding_local_offset {
         content_data_out.buildings = content_data_out
@@ -71,14 +119,19 @@ pub fn process_middle_layer(
 
     if sorting_buildings {
         content_data_out.buildings = sort_buildings(content_data_out.buildings, true);
-        content_data_out.buildings = fix_buildings_index(content_data_out.buildings);
+        let (buildings, warns_fix_index) = fix_buildings_index(content_data_out.buildings);
+        content_data_out.buildings = buildings;
+        warns.extend(warns_fix_index);
     }
 
-    (header_data_out, content_data_out)
+    Ok((header_data_in, content_data_out, warns))
 }
 
 /// 蓝图工具的后端，可编码并输出多种格式的蓝图数据
 ///
+/// 以旧版本输出建筑时，会丢失数据的建筑将以警告的形式返回\
+/// `preserve_unknown`为真时，原样写回header和content末尾的未知数据
+///
 /// # Errors
 /// 所有编码或输出时发生的错误在此汇总
 pub fn process_back_end(
@@ -86,16 +139,61 @@ pub fn process_back_end(
     content_data: &Content,
     zopfli_options: &zopfli::Options,
     output_type: &LegalBlueprintFileType,
-) -> Result<BlueprintKind, DspbptkError> {
-    match output_type {
+    version: &Version,
+    preserve_unknown: bool,
+) -> Result<(BlueprintKind, Vec<DspbptkWarn>), DspbptkError> {
+    let warns = content_data.check_version(version);
+    let blueprint_kind = match output_type {
         LegalBlueprintFileType::Txt => {
-            let header_string = codec::header::serialization(header_data);
-            let content_string = codec::content::string_from_data(content_data, zopfli_options)?;
-            Ok(BlueprintKind::Txt(codec::serialization(
-                &header_string,
-                &content_string,
-            )))
+            let header_string = codec::header::serialization(header_data, preserve_unknown);
+            let content_string = codec::content::string_from_data(
+                content_data,
+                zopfli_options,
+                version,
+                preserve_unknown,
+            )?;
+            BlueprintKind::Txt(codec::serialization(&header_string, &content_string))
         }
-        LegalBlueprintFileType::Content => Ok(BlueprintKind::Content(content_data.to_bin())),
+        LegalBlueprintFileType::Content => {
+            BlueprintKind::Content(content_data.to_bin(version, preserve_unknown))
+        }
+    };
+    Ok((blueprint_kind, warns))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct AddBuildingsLength(u32);
+
+    impl DspbptkMap for AddBuildingsLength {
+        fn apply(&self, content_in: Content) -> Result<(Content, Vec<DspbptkWarn>), DspbptkError> {
+            match self.0 {
+                0 => Err(DspbptkError::UnknownFileType),
+                n => Ok((
+                    Content {
+                        buildings_length: content_in.buildings_length * 10 + n,
+                        ..content_in
+                    },
+                    vec![DspbptkWarn::UnknownAfterHeader],
+                )),
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_chain() {
+        let chain = [AddBuildingsLength(1), AddBuildingsLength(2)];
+        let result = chain.apply(Content::default());
+        assert_eq!(
+            result
+                .map(|(content, warns)| (content.buildings_length, warns.len()))
+                .ok(),
+            Some((12, 2))
+        );
+
+        let chain = [AddBuildingsLength(1), AddBuildingsLength(0)];
+        assert!(chain.apply(Content::default()).is_err());
     }
 }
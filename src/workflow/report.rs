@@ -0,0 +1,144 @@
+use crate::{error::DspbptkWarn, workflow::io::WriteOutcome};
+use std::{
+    fmt::{self, Display},
+    path::PathBuf,
+};
+
+/// 没有输入时视为没有压缩
+#[expect(clippy::cast_precision_loss)]
+fn ratio(size_out: usize, size_in: usize) -> f64 {
+    if size_in == 0 {
+        1.0
+    } else {
+        size_out as f64 / size_in as f64
+    }
+}
+
+/// 单个蓝图文件的处理报告
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub warns: Vec<DspbptkWarn>,
+    /// 输入文件的尺寸
+    pub size_in: usize,
+    /// 输出文件的尺寸
+    pub size_out: usize,
+    pub outcome: WriteOutcome,
+}
+
+impl FileReport {
+    /// 压缩率，即输出尺寸与输入尺寸之比
+    #[must_use]
+    pub fn compression_ratio(&self) -> f64 {
+        ratio(self.size_out, self.size_in)
+    }
+}
+
+impl Display for FileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "\"{}\": {} -> {} bytes ({:.2}%), {} warns",
+            self.path.display(),
+            self.size_in,
+            self.size_out,
+            self.compression_ratio() * 100.0,
+            self.warns.len()
+        )?;
+        match &self.outcome {
+            WriteOutcome::Written(Some(backup_path)) => {
+                write!(f, ", backup: \"{}\"", backup_path.display())
+            }
+            WriteOutcome::Written(None) => Ok(()),
+            WriteOutcome::Skipped => write!(f, ", skipped"),
+        }
+    }
+}
+
+/// 批量处理的汇总
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 成功处理但不满足写入条件的文件数量
+    pub skipped: usize,
+    pub warns: usize,
+    /// 所有成功处理的输入文件的尺寸之和
+    pub size_in: usize,
+    /// 所有成功处理的输出文件的尺寸之和
+    pub size_out: usize,
+}
+
+impl Summary {
+    /// 把单个文件的处理结果计入汇总
+    #[must_use]
+    pub const fn record<E>(mut self, result: &Result<FileReport, E>) -> Self {
+        match result {
+            Ok(report) => {
+                self.succeeded += 1;
+                if matches!(report.outcome, WriteOutcome::Skipped) {
+                    self.skipped += 1;
+                }
+                self.warns += report.warns.len();
+                self.size_in += report.size_in;
+                self.size_out += report.size_out;
+            }
+            Err(_) => self.failed += 1,
+        }
+        self
+    }
+
+    /// 压缩率，即输出尺寸之和与输入尺寸之和的比
+    #[must_use]
+    pub fn compression_ratio(&self) -> f64 {
+        ratio(self.size_out, self.size_in)
+    }
+
+    /// 是否所有文件都处理成功
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} files: {} succeeded, {} failed, {} skipped, {} warns; {} -> {} bytes ({:.2}%)",
+            self.succeeded + self.failed,
+            self.succeeded,
+            self.failed,
+            self.skipped,
+            self.warns,
+            self.size_in,
+            self.size_out,
+            self.compression_ratio() * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let report = FileReport {
+            path: PathBuf::from("a.txt"),
+            warns: vec![DspbptkWarn::UnknownAfterHeader],
+            size_in: 200,
+            size_out: 100,
+            outcome: WriteOutcome::Skipped,
+        };
+        let results: [Result<FileReport, ()>; 3] = [Ok(report.clone()), Ok(report), Err(())];
+        let summary = results.iter().fold(Summary::default(), Summary::record);
+
+        assert_eq!(summary.succeeded, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.skipped, 2);
+        assert_eq!(summary.warns, 2);
+        assert!((summary.compression_ratio() - 0.5).abs() < f64::EPSILON);
+        assert!(!summary.is_success());
+    }
+}